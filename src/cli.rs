@@ -0,0 +1,107 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/cli.rs
+
+use clap::builder::RangedU64ValueParser;
+use clap::Parser;
+use rusty_game_of_life_with_bevy::plugin::{MAX_CYCLE_WINDOW, MAX_GRID_SIZE, MAX_HISTORY_LIMIT};
+use rusty_game_of_life_with_bevy::{Neighborhood, Ruleset};
+
+// Command-line options, e.g. `cargo run -- --size 100 --seed 42 --rule B36/S23`. Anything
+// left out falls back to config.toml and then to the defaults.
+#[derive(Parser, Debug)]
+#[command(about = "Conway's Game of Life, and other cellular automata, in Bevy")]
+pub struct Cli {
+    /// Width and height of the grid [default: 50]
+    #[arg(long, value_name = "N", value_parser = count(MAX_GRID_SIZE))]
+    pub size: Option<usize>,
+
+    /// Width of the grid, overriding --size
+    #[arg(long, value_name = "N", value_parser = count(MAX_GRID_SIZE))]
+    pub width: Option<usize>,
+
+    /// Height of the grid, overriding --size
+    #[arg(long, value_name = "N", value_parser = count(MAX_GRID_SIZE))]
+    pub height: Option<usize>,
+
+    /// Birth/survival rule, e.g. B36/S23, or a Generations rule such as B2/S/C3 [default: B3/S23]
+    #[arg(long, value_parser = parse_rule)]
+    pub rule: Option<Ruleset>,
+
+    /// Which cells count as neighbours: moore, von-neumann or moore:N [default: moore]
+    #[arg(long, value_parser = parse_neighborhood)]
+    pub neighborhood: Option<Neighborhood>,
+
+    /// Seed for the random board, so a run can be repeated [default: random]
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    /// Start from an RLE, Life 1.06 (.lif) or plaintext (.cells) pattern
+    #[arg(long, value_name = "FILE")]
+    pub pattern: Option<String>,
+
+    /// Steps and edits kept for undo and rewinding, 0 turning undo off [default: 100]
+    #[arg(long, value_name = "N", value_parser = count_or_zero(MAX_HISTORY_LIMIT))]
+    pub history: Option<usize>,
+
+    /// Pause once the board repeats one of its recent generations
+    #[arg(long)]
+    pub auto_pause: bool,
+
+    /// How long a dead cell takes to fade out [default: 0.5]
+    #[arg(long, value_name = "S", value_parser = parse_seconds)]
+    pub fade_seconds: Option<f32>,
+
+    /// Re-randomize the board as soon as everything has died
+    #[arg(long)]
+    pub restart_on_empty: bool,
+
+    /// How many generations back --auto-pause looks for a repeat [default: 30]
+    #[arg(long, value_name = "K", value_parser = count(MAX_CYCLE_WINDOW))]
+    pub cycle_window: Option<usize>,
+
+    /// Step an unbounded world instead of the grid
+    #[arg(long)]
+    pub infinite: bool,
+
+    /// Run N generations without a window, print the timings and exit
+    #[arg(long, value_name = "N")]
+    pub headless: Option<u64>,
+
+    /// Append every generation to FILE as the simulation runs
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<String>,
+
+    /// Play a recording back instead of simulating
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<String>,
+
+    /// Pause once the board reaches generation N
+    #[arg(long = "max-gens", value_name = "N")]
+    pub max_generations: Option<u64>,
+}
+
+// A count between 1 and max
+fn count(max: usize) -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::new().range(1..=max as u64)
+}
+
+// A count between 0 and max
+fn count_or_zero(max: usize) -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::new().range(0..=max as u64)
+}
+
+fn parse_rule(rule: &str) -> Result<Ruleset, String> {
+    Ruleset::parse(rule).map_err(|err| err.to_string())
+}
+
+fn parse_neighborhood(name: &str) -> Result<Neighborhood, String> {
+    Neighborhood::parse(name).ok_or_else(|| "expected moore, von-neumann or moore:N".to_string())
+}
+
+fn parse_seconds(seconds: &str) -> Result<f32, String> {
+    match seconds.parse::<f32>() {
+        Ok(seconds) if seconds > 0.0 => Ok(seconds),
+        Ok(_) => Err("must be more than 0".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
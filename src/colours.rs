@@ -1,5 +1,81 @@
 // Package: rusty_game_of_life_with_bevy
 // File: src/colours.rs
 
+use crate::CellState;
+use bevy::prelude::*;
 
+// The colour for each cell state, cycled through PRESETS with T
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub newly_alive: Color,
+    pub recently_dead: Color,
+    pub alive: Color,
+    pub dead: Color,
+    pub dying: Color, // The first dying state under a Generations rule
+}
 
+pub const CLASSIC: Theme = Theme {
+    name: "classic",
+    newly_alive: Color::GREEN,
+    recently_dead: Color::RED,
+    alive: Color::WHITE,
+    dead: Color::BLACK,
+    dying: Color::BLUE,
+};
+
+pub const MATRIX: Theme = Theme {
+    name: "matrix",
+    newly_alive: Color::rgb(0.6, 1.0, 0.6),
+    recently_dead: Color::rgb(0.0, 0.25, 0.0),
+    alive: Color::rgb(0.0, 0.8, 0.0),
+    dead: Color::BLACK,
+    dying: Color::rgb(0.0, 0.5, 0.3),
+};
+
+// Blue and orange stay distinct for the common kinds of colour blindness
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high contrast",
+    newly_alive: Color::rgb(0.0, 0.45, 0.7),
+    recently_dead: Color::rgb(0.9, 0.6, 0.0),
+    alive: Color::WHITE,
+    dead: Color::BLACK,
+    dying: Color::rgb(0.8, 0.475, 0.655),
+};
+
+pub const PRESETS: [Theme; 3] = [CLASSIC, MATRIX, HIGH_CONTRAST];
+
+impl Default for Theme {
+    fn default() -> Self {
+        CLASSIC
+    }
+}
+
+impl Theme {
+    pub fn color(&self, state: CellState) -> Color {
+        match state {
+            CellState::NewlyAlive => self.newly_alive,
+            CellState::RecentlyDead => self.recently_dead,
+            CellState::Alive => self.alive,
+            CellState::Dead => self.dead,
+        }
+    }
+
+    // The preset after this one, wrapping around
+    pub fn next(&self) -> Theme {
+        let index = PRESETS.iter().position(|theme| theme.name == self.name);
+        PRESETS[index.map_or(0, |i| (i + 1) % PRESETS.len())]
+    }
+}
+
+// Blends linearly from `from` (t = 0) to `to` (t = 1)
+pub fn mix(from: Color, to: Color, t: f32) -> Color {
+    let [r1, g1, b1, a1] = from.as_rgba_f32();
+    let [r2, g2, b2, a2] = to.as_rgba_f32();
+    Color::rgba(
+        r1 + (r2 - r1) * t,
+        g1 + (g2 - g1) * t,
+        b1 + (b2 - b1) * t,
+        a1 + (a2 - a1) * t,
+    )
+}
@@ -0,0 +1,148 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/config.rs
+
+use bevy::prelude::Color;
+use rusty_game_of_life_with_bevy::colours::{self, Theme};
+use rusty_game_of_life_with_bevy::plugin::{MAX_GRID_SIZE, MAX_TICK_RATE, MIN_TICK_RATE};
+use rusty_game_of_life_with_bevy::{GameOfLifePlugin, Ruleset};
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+
+// Read at startup from the working directory; command-line options override it
+pub const CONFIG_PATH: &str = "config.toml";
+
+// Settings from the config file. Everything is optional, and anything left out keeps its
+// default. When the grid isn't square, `width` and `height` override `size`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    size: Option<usize>,
+    width: Option<usize>,
+    height: Option<usize>,
+    tick_rate: Option<f32>,
+    density: Option<f64>,
+    rule: Option<String>,
+    theme: Option<String>, // One of the presets T cycles through
+    colours: Colours,
+}
+
+// Hex colours such as "#ffcc00", each replacing that part of the theme
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Colours {
+    newly_alive: Option<String>,
+    recently_dead: Option<String>,
+    alive: Option<String>,
+    dead: Option<String>,
+    dying: Option<String>,
+}
+
+// An empty config when the file is missing or can't be parsed. This runs before the app
+// has a logger, so it prints.
+pub fn load(path: &str) -> FileConfig {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            println!("No {} found, using the defaults", path);
+            return FileConfig::default();
+        }
+        Err(err) => {
+            eprintln!("Failed to read {}: {}, using the defaults", path, err);
+            return FileConfig::default();
+        }
+    };
+    toml::from_str(&contents).unwrap_or_else(|err| {
+        eprintln!("Invalid {}: {}, using the defaults", path, err);
+        FileConfig::default()
+    })
+}
+
+impl FileConfig {
+    // The plugin with each valid setting copied in, reporting and skipping the rest
+    pub fn apply(self, mut plugin: GameOfLifePlugin) -> GameOfLifePlugin {
+        let size = grid_size("size", self.size);
+        if let Some(width) = grid_size("width", self.width).or(size) {
+            plugin.config.grid_width = width;
+        }
+        if let Some(height) = grid_size("height", self.height).or(size) {
+            plugin.config.grid_height = height;
+        }
+        match self.tick_rate {
+            Some(rate) if (MIN_TICK_RATE..=MAX_TICK_RATE).contains(&rate) => {
+                plugin.config.tick_rate = rate
+            }
+            Some(rate) => eprintln!(
+                "tick_rate {} in {} must be between {} and {} seconds, ignoring it",
+                rate, CONFIG_PATH, MIN_TICK_RATE, MAX_TICK_RATE
+            ),
+            None => {}
+        }
+        match self.density {
+            Some(density) if (0.0..=1.0).contains(&density) => plugin.density = density,
+            Some(density) => eprintln!(
+                "density {} in {} must be between 0 and 1, ignoring it",
+                density, CONFIG_PATH
+            ),
+            None => {}
+        }
+        if let Some(rule) = self.rule {
+            match Ruleset::parse(&rule) {
+                Ok(ruleset) => plugin.ruleset = ruleset,
+                Err(err) => eprintln!("Invalid rule '{}' in {}: {}", rule, CONFIG_PATH, err),
+            }
+        }
+        if let Some(name) = self.theme {
+            match colours::PRESETS
+                .iter()
+                .find(|theme| theme.name.eq_ignore_ascii_case(&name))
+            {
+                Some(&theme) => plugin.theme = theme,
+                None => eprintln!("Unknown theme '{}' in {}", name, CONFIG_PATH),
+            }
+        }
+        self.colours.apply(&mut plugin.theme);
+        plugin
+    }
+}
+
+impl Colours {
+    fn apply(self, theme: &mut Theme) {
+        let parts = [
+            ("newly_alive", self.newly_alive, &mut theme.newly_alive),
+            (
+                "recently_dead",
+                self.recently_dead,
+                &mut theme.recently_dead,
+            ),
+            ("alive", self.alive, &mut theme.alive),
+            ("dead", self.dead, &mut theme.dead),
+            ("dying", self.dying, &mut theme.dying),
+        ];
+        for (name, hex, colour) in parts {
+            let Some(hex) = hex else {
+                continue;
+            };
+            match Color::hex(hex.trim_start_matches('#')) {
+                Ok(parsed) => *colour = parsed,
+                Err(err) => eprintln!(
+                    "Invalid colour '{}' for {} in {}: {}",
+                    hex, name, CONFIG_PATH, err
+                ),
+            }
+        }
+    }
+}
+
+// The size if it was given and is a usable grid dimension
+fn grid_size(name: &str, size: Option<usize>) -> Option<usize> {
+    let size = size?;
+    if (1..=MAX_GRID_SIZE).contains(&size) {
+        return Some(size);
+    }
+    eprintln!(
+        "{} {} in {} must be between 1 and {}, ignoring it",
+        name, size, CONFIG_PATH, MAX_GRID_SIZE
+    );
+    None
+}
@@ -0,0 +1,557 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/grid.rs
+
+use crate::patterns::{cell_count, Pattern, MAX_PATTERN_CELLS};
+use crate::rle;
+use crate::rules::{CellularAutomaton, Ruleset};
+use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::{fmt, fs, io};
+
+// Default for `Grid::cycle_window`
+pub const CYCLE_WINDOW: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    Bounded,  // Cells beyond the edge count as dead
+    Toroidal, // Opposite edges are joined
+}
+
+// Which cells around a cell count as its neighbours. Counting visits (2r + 1)^2 cells for
+// each cell at range r, so a step at range 5 does about 13 times the work of one at range 1.
+// Rulesets only distinguish counts up to 8.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    #[default]
+    Moore, // The 8 surrounding cells
+    VonNeumann,     // Only the 4 orthogonal cells
+    MooreRange(u8), // Every cell within this many steps along each axis, e.g. 24 at range 2
+}
+
+impl Neighborhood {
+    // How far the neighbourhood reaches along each axis
+    pub fn range(self) -> usize {
+        match self {
+            Neighborhood::Moore | Neighborhood::VonNeumann => 1,
+            Neighborhood::MooreRange(range) => range as usize,
+        }
+    }
+
+    // Whether the cell at offset (dx, dy) is a neighbour; a cell never neighbours itself
+    pub fn contains(self, dx: isize, dy: isize) -> bool {
+        match self {
+            Neighborhood::VonNeumann => dx.abs() + dy.abs() == 1,
+            _ => (dx, dy) != (0, 0) && dx.abs().max(dy.abs()) <= self.range() as isize,
+        }
+    }
+
+    // `moore`, `von-neumann` or `moore:N` for range N of at least 1
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "moore" => Some(Neighborhood::Moore),
+            "von-neumann" | "vonneumann" => Some(Neighborhood::VonNeumann),
+            other => {
+                let range = other.strip_prefix("moore:")?.parse().ok()?;
+                (range > 0).then_some(Neighborhood::MooreRange(range))
+            }
+        }
+    }
+}
+
+// The names `parse` accepts
+impl fmt::Display for Neighborhood {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Neighborhood::Moore => write!(f, "moore"),
+            Neighborhood::VonNeumann => write!(f, "von-neumann"),
+            Neighborhood::MooreRange(range) => write!(f, "moore:{}", range),
+        }
+    }
+}
+
+// Returned by `Grid::rotate_cw` for a grid that isn't square, with its width and height
+#[derive(Debug, PartialEq, Eq)]
+pub struct NotSquare(pub usize, pub usize);
+
+impl fmt::Display for NotSquare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "can't rotate a {}x{} grid, only a square one",
+            self.0, self.1
+        )
+    }
+}
+
+// How a cell changed in the last step, which the frontend uses to pick its colour
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellState {
+    Dead,
+    NewlyAlive,
+    Alive,
+    RecentlyDead,
+}
+
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<bool>,
+    pub prev_cells: Vec<bool>, // Tracks the previous state of cells
+    pub topology: Topology,
+    pub neighborhood: Neighborhood,
+    pub ruleset: Ruleset,
+    pub generation: u64, // Steps taken since the board was last cleared or randomized
+    pub age: Vec<u32>,   // Generations each live cell has survived, 0 for dead cells
+    pub dying: Vec<u8>,  // Generations rules: the state (2 or more) of each dying cell, else 0
+    pub stable: bool,    // The last step changed nothing; cleared by any edit
+    pub oscillating: bool, // The last step returned to the board from two generations ago
+    pub period: Option<usize>, // The last step repeated a board this many generations back
+    pub cycle_window: usize, // How many generations back `period` looks
+    back: Vec<bool>,     // Scratch buffer the next generation is computed into
+    clean_steps: u8,     // Steps since the last edit, capped at 2
+    recent: VecDeque<u64>, // Hashes of the boards since the last edit, newest last
+}
+
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_topology(width, height, Topology::Bounded)
+    }
+
+    pub fn new_square(size: usize) -> Self {
+        Self::new(size, size)
+    }
+
+    pub fn with_topology(width: usize, height: usize, topology: Topology) -> Self {
+        let total_cells = width * height;
+        let cells = vec![false; total_cells]; // All cells start dead
+        let prev_cells = vec![false; total_cells];
+        Self {
+            width,
+            height,
+            cells,
+            prev_cells,
+            topology,
+            neighborhood: Neighborhood::default(),
+            ruleset: Ruleset::default(),
+            generation: 0,
+            age: vec![0; total_cells],
+            dying: vec![0; total_cells],
+            stable: false,
+            oscillating: false,
+            period: None,
+            cycle_window: CYCLE_WINDOW,
+            clean_steps: 0,
+            back: vec![false; total_cells],
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.width + x]
+    }
+
+    // Edited cells start their age afresh
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let index = y * self.width + x;
+        self.cells[index] = value;
+        self.age[index] = 0;
+        self.dying[index] = 0;
+        self.mark_edited();
+    }
+
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        let index = y * self.width + x;
+        self.cells[index] = !self.cells[index];
+        self.age[index] = 0;
+        self.dying[index] = 0;
+        self.mark_edited();
+    }
+
+    pub fn randomize(&mut self, density: f64, rng: &mut impl Rng) {
+        for cell in self.cells.iter_mut() {
+            *cell = rng.gen_bool(density);
+        }
+        // Match the previous state so the first frame isn't all "newly alive"
+        self.prev_cells.copy_from_slice(&self.cells);
+        self.age.fill(0);
+        self.dying.fill(0);
+        self.generation = 0;
+        self.mark_edited();
+    }
+
+    // Sets the pattern's live cells with its top-left corner at (x, y), clipping at the edges.
+    // Grid y grows upwards on screen, so pattern rows are laid out towards lower y.
+    pub fn insert_pattern(&mut self, x: usize, y: usize, pattern: &Pattern) {
+        for row in 0..pattern.height {
+            for col in 0..pattern.width {
+                if !pattern.get(col, row) {
+                    continue;
+                }
+                let (gx, gy) = (x + col, y as isize - row as isize);
+                if gx < self.width && gy >= 0 && (gy as usize) < self.height {
+                    self.set(gx, gy as usize, true);
+                }
+            }
+        }
+    }
+
+    // The whole board as a pattern, top row first
+    pub fn to_pattern(&self) -> Pattern {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for y in (0..self.height).rev() {
+            cells.extend((0..self.width).map(|x| self.get(x, y)));
+        }
+        Pattern {
+            width: self.width,
+            height: self.height,
+            cells,
+        }
+    }
+
+    pub fn to_rle(&self) -> String {
+        rle::to_rle(&self.to_pattern(), &self.ruleset.to_string())
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(false);
+        self.prev_cells.fill(false); // No stale "recently dead" cells
+        self.age.fill(0);
+        self.dying.fill(0);
+        self.generation = 0;
+        self.mark_edited();
+    }
+
+    // Changes the grid to width x height, keeping the cells that fit. Coordinates stay
+    // anchored at (0, 0), so growing adds dead cells and shrinking drops the rest.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let mut resized = Grid::with_topology(width, height, self.topology);
+        for y in 0..self.height.min(height) {
+            for x in 0..self.width.min(width) {
+                resized.cells[y * width + x] = self.cells[y * self.width + x];
+                resized.prev_cells[y * width + x] = self.prev_cells[y * self.width + x];
+                resized.age[y * width + x] = self.age[y * self.width + x];
+                resized.dying[y * width + x] = self.dying[y * self.width + x];
+            }
+        }
+        resized.neighborhood = self.neighborhood;
+        resized.cycle_window = self.cycle_window;
+        resized.ruleset = self.ruleset;
+        resized.generation = self.generation;
+        *self = resized;
+    }
+
+    // Moves every cell by (dx, dy), wrapping round the edges of a toroidal grid and dropping
+    // whatever is pushed off a bounded one. Previous states, ages and dying stages move too,
+    // so the colours stay as they were.
+    pub fn shift_grid(&mut self, dx: isize, dy: isize) {
+        self.move_cells(|grid, x, y| grid.wrap(x as isize + dx, y as isize + dy));
+    }
+
+    // Mirrors the board left to right
+    pub fn flip_h(&mut self) {
+        self.move_cells(|grid, x, y| Some((grid.width - 1 - x, y)));
+    }
+
+    // Mirrors the board top to bottom
+    pub fn flip_v(&mut self) {
+        self.move_cells(|grid, x, y| Some((x, grid.height - 1 - y)));
+    }
+
+    // Turns the board a quarter turn clockwise as drawn, with y growing upwards. Only square
+    // grids keep their shape, so any other is left alone.
+    pub fn rotate_cw(&mut self) -> Result<(), NotSquare> {
+        if self.width != self.height {
+            return Err(NotSquare(self.width, self.height));
+        }
+        self.move_cells(|grid, x, y| Some((y, grid.width - 1 - x)));
+        Ok(())
+    }
+
+    // Moves each cell, along with its previous state, age and dying stage, to wherever `to`
+    // sends it; cells sent nowhere are dropped
+    fn move_cells(&mut self, to: impl Fn(&Self, usize, usize) -> Option<(usize, usize)>) {
+        let destinations: Vec<_> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| to(self, x, y).map(|(nx, ny)| ny * self.width + nx))
+            .collect();
+        self.cells = moved(&self.cells, &destinations);
+        self.prev_cells = moved(&self.prev_cells, &destinations);
+        self.age = moved(&self.age, &destinations);
+        self.dying = moved(&self.dying, &destinations);
+        self.mark_edited();
+    }
+
+    // Counts live cells in the grid's neighbourhood. On a toroidal grid smaller than the
+    // neighbourhood the same cell can be counted more than once.
+    pub fn neighbors(&self, x: usize, y: usize) -> usize {
+        let range = self.neighborhood.range() as isize;
+        let mut count = 0;
+        for dx in -range..=range {
+            for dy in -range..=range {
+                if !self.neighborhood.contains(dx, dy) {
+                    continue;
+                }
+                let cell = self.wrap(x as isize + dx, y as isize + dy);
+                if cell.is_some_and(|(nx, ny)| self.get(nx, ny)) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    // Where (x, y) lands under the grid's topology: wrapped round on a toroidal grid, or
+    // `None` beyond the edge of a bounded one
+    pub fn wrap(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        match self.topology {
+            Topology::Bounded if x < 0 || y < 0 || x >= width || y >= height => None,
+            Topology::Bounded => Some((x as usize, y as usize)),
+            Topology::Toroidal => {
+                Some((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize))
+            }
+        }
+    }
+
+    // Rotates the three buffers rather than allocating: the back buffer receives the next
+    // generation, the current cells become `prev_cells`, and the old previous state is
+    // reused as the next back buffer
+    pub fn step(&mut self) {
+        let ruleset = self.ruleset;
+        self.step_with(&ruleset);
+    }
+
+    // Steps with another automaton in place of the grid's own ruleset. Dying cells are
+    // never reborn, whichever automaton runs.
+    pub fn step_with<A: CellularAutomaton<Cell = bool>>(&mut self, automaton: &A) {
+        let mut next = std::mem::take(&mut self.back);
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let index = y * self.width + x;
+                let alive = self.cells[index];
+                let neighbors = self.neighbors(x, y);
+                next[index] = self.dying[index] == 0 && automaton.next_state(alive, neighbors);
+            }
+        }
+        self.finish_step(next);
+    }
+
+    // Same result as `step`, but computes each row of the next generation on the rayon
+    // thread pool. Only worth it for large grids; small ones are faster single-threaded.
+    #[cfg(feature = "parallel")]
+    pub fn step_parallel(&mut self) {
+        use rayon::prelude::*;
+
+        let mut next = std::mem::take(&mut self.back);
+        let this = &*self;
+        next.par_chunks_mut(this.width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    let neighbors = this.neighbors(x, y);
+                    *cell = this.dying[y * this.width + x] == 0
+                        && this.ruleset.next_state(this.get(x, y), neighbors);
+                }
+            });
+        self.finish_step(next);
+    }
+
+    // After rotating, `back` holds the board from two generations ago. Comparing against
+    // it is only meaningful once two steps have run without an edit in between. Longer
+    // cycles are found by hash, against the boards since the last edit.
+    fn finish_step(&mut self, next: Vec<bool>) {
+        if self.recent.is_empty() {
+            self.recent.push_back(hash_cells(&self.cells, &self.dying));
+        }
+        std::mem::swap(&mut self.prev_cells, &mut self.cells);
+        self.back = std::mem::replace(&mut self.cells, next);
+        self.generation += 1;
+        for (age, &alive) in self.age.iter_mut().zip(&self.cells) {
+            *age = if alive { age.saturating_add(1) } else { 0 };
+        }
+        let dying = self.decay();
+        self.clean_steps = (self.clean_steps + 1).min(2);
+        self.stable = self.cells == self.prev_cells && !dying;
+        self.oscillating = !self.stable && self.clean_steps == 2 && self.cells == self.back;
+
+        let hash = hash_cells(&self.cells, &self.dying);
+        self.period = self
+            .recent
+            .iter()
+            .rev()
+            .position(|&earlier| earlier == hash)
+            .map(|offset| offset + 1);
+        self.recent.push_back(hash);
+        while self.recent.len() > self.cycle_window {
+            self.recent.pop_front();
+        }
+    }
+
+    // Cells that just died start dying at state 2 and advance a state each generation
+    // until they pass the last one. Returns whether any cell is still dying.
+    fn decay(&mut self) -> bool {
+        let states = self.ruleset.states;
+        if states <= 2 && !self.dying.iter().any(|&d| d > 0) {
+            return false;
+        }
+        let mut any = false;
+        for (i, dying) in self.dying.iter_mut().enumerate() {
+            *dying = if self.prev_cells[i] && !self.cells[i] {
+                2
+            } else if *dying > 0 {
+                *dying + 1
+            } else {
+                0
+            };
+            if *dying >= states {
+                *dying = 0;
+            }
+            any |= *dying > 0;
+        }
+        any
+    }
+
+    // Call after changing `cells` directly, so stability isn't judged across the edit
+    pub fn mark_edited(&mut self) {
+        self.stable = false;
+        self.oscillating = false;
+        self.period = None;
+        self.clean_steps = 0;
+        self.recent.clear();
+    }
+
+    // Writes `width height` on the first line, then one row per line with `O` for alive
+    // and `.` for dead
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = String::with_capacity((self.width + 1) * self.height + 16);
+        out.push_str(&format!("{} {}\n", self.width, self.height));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                out.push(if self.get(x, y) { 'O' } else { '.' });
+            }
+            out.push('\n');
+        }
+        fs::write(path, out)
+    }
+
+    // Reads the format written by `save_to_path`. Older saves of square grids have only
+    // a single size on the first line.
+    pub fn load_from_path(path: impl AsRef<Path>) -> io::Result<Grid> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+        let (width, height) = lines
+            .next()
+            .and_then(parse_dimensions)
+            .ok_or_else(|| invalid_data("missing grid size header".to_string()))?;
+        // Checked before the cells are allocated, so a bad header can't ask for any size
+        if cell_count(width, height).is_none() {
+            let message = format!(
+                "{}x{} is more than {} cells",
+                width, height, MAX_PATTERN_CELLS
+            );
+            return Err(invalid_data(message));
+        }
+        let mut grid = Grid::new(width, height);
+        for y in 0..height {
+            let row = lines
+                .next()
+                .ok_or_else(|| invalid_data(format!("missing row {}", y)))?;
+            if row.chars().count() != width {
+                let message = format!("row {} is not {} cells wide", y, width);
+                return Err(invalid_data(message));
+            }
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    'O' => grid.set(x, y, true),
+                    '.' => {}
+                    _ => return Err(invalid_data(format!("unexpected character '{}'", c))),
+                }
+            }
+        }
+        grid.prev_cells.copy_from_slice(&grid.cells);
+        Ok(grid)
+    }
+
+    pub fn count_live(&self) -> usize {
+        self.cells.iter().filter(|&&c| c).count()
+    }
+
+    // Draws each cell as a `scale` pixel square in the RGBA colour `color(x, y)` picks for it.
+    // Grid y grows upwards and image rows run downwards, so the top row is drawn first.
+    #[cfg(feature = "png")]
+    pub fn render_to_image(
+        &self,
+        scale: u32,
+        color: impl Fn(usize, usize) -> [u8; 4],
+    ) -> image::RgbaImage {
+        let (width, height) = (self.width as u32 * scale, self.height as u32 * scale);
+        image::RgbaImage::from_fn(width, height, |px, py| {
+            let x = (px / scale) as usize;
+            let y = self.height - 1 - (py / scale) as usize;
+            image::Rgba(color(x, y))
+        })
+    }
+
+    pub fn cell_age(&self, x: usize, y: usize) -> u32 {
+        self.age[y * self.width + x]
+    }
+
+    // 0 for dead, 1 for alive, 2 and up for the dying states of a Generations rule
+    pub fn state(&self, x: usize, y: usize) -> u8 {
+        let index = y * self.width + x;
+        if self.cells[index] {
+            1
+        } else {
+            self.dying[index]
+        }
+    }
+
+    pub fn cell_state(&self, x: usize, y: usize) -> CellState {
+        let current = self.cells[y * self.width + x];
+        let previous = self.prev_cells[y * self.width + x];
+        match (previous, current) {
+            (false, true) => CellState::NewlyAlive,
+            (true, false) => CellState::RecentlyDead,
+            (true, true) => CellState::Alive,
+            (false, false) => CellState::Dead,
+        }
+    }
+}
+
+// `values` rearranged so the value at index i lands at `destinations[i]`, with anything
+// sent nowhere reset to its default
+fn moved<T: Copy + Default>(values: &[T], destinations: &[Option<usize>]) -> Vec<T> {
+    let mut moved = vec![T::default(); values.len()];
+    for (&value, destination) in values.iter().zip(destinations) {
+        if let Some(i) = *destination {
+            moved[i] = value;
+        }
+    }
+    moved
+}
+
+fn hash_cells(cells: &[bool], dying: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cells.hash(&mut hasher);
+    dying.hash(&mut hasher);
+    hasher.finish()
+}
+
+// `width height`, or a single size for a square grid
+fn parse_dimensions(line: &str) -> Option<(usize, usize)> {
+    let mut fields = line.split_whitespace().map(|field| field.parse().ok());
+    let width = fields.next()??;
+    let height = fields.next().unwrap_or(Some(width))?;
+    match fields.next() {
+        Some(_) => None,
+        None => Some((width, height)),
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
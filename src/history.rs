@@ -0,0 +1,122 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/history.rs
+
+use crate::grid::Grid;
+use std::collections::VecDeque;
+
+// Memory the past snapshots may take together, so a long history of a large grid doesn't
+// exhaust it; a 1000x1000 snapshot is about 6 MB
+pub const MAX_HISTORY_BYTES: usize = 256 << 20;
+
+// The board as it was before a step or edit
+struct Snapshot {
+    cells: Vec<bool>,
+    age: Vec<u32>,
+    dying: Vec<u8>,
+    generation: u64,
+}
+
+impl Snapshot {
+    fn of(grid: &Grid) -> Self {
+        Self {
+            cells: grid.cells.clone(),
+            age: grid.age.clone(),
+            dying: grid.dying.clone(),
+            generation: grid.generation,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        self.cells.len() + self.age.len() * std::mem::size_of::<u32>() + self.dying.len()
+    }
+
+    // Puts this snapshot back into the grid, returning the state it replaced
+    fn restore(self, grid: &mut Grid) -> Snapshot {
+        let replaced = Snapshot {
+            cells: std::mem::replace(&mut grid.cells, self.cells),
+            age: std::mem::replace(&mut grid.age, self.age),
+            dying: std::mem::replace(&mut grid.dying, self.dying),
+            generation: std::mem::replace(&mut grid.generation, self.generation),
+        };
+        grid.prev_cells.copy_from_slice(&grid.cells);
+        grid.mark_edited();
+        replaced
+    }
+}
+
+// Snapshots taken before each step or edit. The past is a ring buffer of at most `limit`
+// entries that together fit in `max_bytes`, so undoing or rewinding only reaches that far
+// back, and any new step or edit drops the redo states. A limit of 0 keeps nothing.
+// Snapshots only fit a grid of the size they were taken at, so clear the history when the
+// grid is resized.
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct History {
+    past: VecDeque<Snapshot>,
+    future: Vec<Snapshot>,
+    limit: usize,
+    max_bytes: usize,
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        Self::with_max_bytes(limit, MAX_HISTORY_BYTES)
+    }
+
+    pub fn with_max_bytes(limit: usize, max_bytes: usize) -> Self {
+        Self {
+            past: VecDeque::new(),
+            future: Vec::new(),
+            limit,
+            max_bytes,
+        }
+    }
+
+    // Call before mutating the grid
+    pub fn record(&mut self, grid: &Grid) {
+        self.future.clear();
+        self.push(Snapshot::of(grid));
+    }
+
+    pub fn undo(&mut self, grid: &mut Grid) -> bool {
+        let Some(snapshot) = self.past.pop_back() else {
+            return false;
+        };
+        self.future.push(snapshot.restore(grid));
+        true
+    }
+
+    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+        let Some(snapshot) = self.future.pop() else {
+            return false;
+        };
+        let replaced = snapshot.restore(grid);
+        self.push(replaced);
+        true
+    }
+
+    // Adds to the past, dropping the oldest snapshots to stay within both limits
+    fn push(&mut self, snapshot: Snapshot) {
+        let fits = self.max_bytes / snapshot.bytes().max(1);
+        let kept = self.limit.min(fits);
+        if kept == 0 {
+            return;
+        }
+        while self.past.len() >= kept {
+            self.past.pop_front();
+        }
+        self.past.push_back(snapshot);
+    }
+
+    pub fn undo_count(&self) -> usize {
+        self.past.len()
+    }
+
+    pub fn redo_count(&self) -> usize {
+        self.future.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+}
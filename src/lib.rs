@@ -0,0 +1,38 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/lib.rs
+
+//! The Game of Life simulation, which doesn't need Bevy: a [`Grid`] stepped by a
+//! birth/survival [`Ruleset`], plus parsers for common pattern file formats. The bit-packed
+//! [`BitGrid`] and the unbounded [`SparseGrid`] are alternative boards for large worlds, and
+//! all three implement [`Life`].
+//!
+//! With the `bevy` feature (enabled by default) the crate also has the Bevy frontend:
+//! [`GameOfLifePlugin`] adds the whole windowed game to an app, and [`Grid`] derives
+//! `Resource` so it can be inserted into one directly. The binary in `main.rs` only reads
+//! the command line and config.toml into the plugin. Build with `--no-default-features` to
+//! use the simulation headlessly.
+
+pub mod bitgrid;
+#[cfg(feature = "bevy")]
+pub mod colours;
+pub mod grid;
+pub mod history;
+pub mod life;
+pub mod life106;
+pub mod patterns;
+#[cfg(feature = "bevy")]
+pub mod plugin;
+pub mod recording;
+pub mod rle;
+pub mod rules;
+pub mod sparse;
+
+pub use bitgrid::BitGrid;
+pub use grid::{CellState, Grid, Neighborhood, NotSquare, Topology};
+pub use history::History;
+pub use life::Life;
+pub use patterns::{Pattern, PatternLibrary};
+#[cfg(feature = "bevy")]
+pub use plugin::GameOfLifePlugin;
+pub use rules::{CellularAutomaton, RuleParseError, Ruleset};
+pub use sparse::SparseGrid;
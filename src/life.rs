@@ -0,0 +1,111 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/life.rs
+
+use crate::bitgrid::BitGrid;
+use crate::grid::Grid;
+use crate::sparse::SparseGrid;
+
+// What a frontend needs from a board, whatever its storage. Coordinates are signed so the
+// unbounded `SparseGrid` fits; the fixed-size grids read cells off the edge as dead and
+// ignore writes there.
+pub trait Life {
+    fn get(&self, x: i64, y: i64) -> bool;
+    fn set(&mut self, x: i64, y: i64, value: bool);
+    fn toggle(&mut self, x: i64, y: i64);
+    fn step(&mut self);
+    fn count_live(&self) -> usize;
+    fn generation(&self) -> u64;
+}
+
+// Converts signed coordinates for a `width` x `height` board, or None off its edge
+fn index(x: i64, y: i64, width: usize, height: usize) -> Option<(usize, usize)> {
+    let x = usize::try_from(x).ok().filter(|&x| x < width)?;
+    let y = usize::try_from(y).ok().filter(|&y| y < height)?;
+    Some((x, y))
+}
+
+impl Life for Grid {
+    fn get(&self, x: i64, y: i64) -> bool {
+        index(x, y, self.width, self.height).is_some_and(|(x, y)| Grid::get(self, x, y))
+    }
+
+    fn set(&mut self, x: i64, y: i64, value: bool) {
+        if let Some((x, y)) = index(x, y, self.width, self.height) {
+            Grid::set(self, x, y, value);
+        }
+    }
+
+    fn toggle(&mut self, x: i64, y: i64) {
+        if let Some((x, y)) = index(x, y, self.width, self.height) {
+            Grid::toggle(self, x, y);
+        }
+    }
+
+    fn step(&mut self) {
+        Grid::step(self);
+    }
+
+    fn count_live(&self) -> usize {
+        Grid::count_live(self)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Life for BitGrid {
+    fn get(&self, x: i64, y: i64) -> bool {
+        index(x, y, self.width, self.height).is_some_and(|(x, y)| BitGrid::get(self, x, y))
+    }
+
+    fn set(&mut self, x: i64, y: i64, value: bool) {
+        if let Some((x, y)) = index(x, y, self.width, self.height) {
+            BitGrid::set(self, x, y, value);
+        }
+    }
+
+    fn toggle(&mut self, x: i64, y: i64) {
+        if let Some((x, y)) = index(x, y, self.width, self.height) {
+            BitGrid::toggle(self, x, y);
+        }
+    }
+
+    fn step(&mut self) {
+        BitGrid::step(self);
+    }
+
+    fn count_live(&self) -> usize {
+        BitGrid::count_live(self)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Life for SparseGrid {
+    fn get(&self, x: i64, y: i64) -> bool {
+        SparseGrid::get(self, x, y)
+    }
+
+    fn set(&mut self, x: i64, y: i64, value: bool) {
+        SparseGrid::set(self, x, y, value);
+    }
+
+    fn toggle(&mut self, x: i64, y: i64) {
+        SparseGrid::toggle(self, x, y);
+    }
+
+    fn step(&mut self) {
+        SparseGrid::step(self);
+    }
+
+    fn count_live(&self) -> usize {
+        SparseGrid::count_live(self)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
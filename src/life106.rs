@@ -0,0 +1,97 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/life106.rs
+
+use crate::patterns::{cell_count, Pattern, MAX_PATTERN_CELLS};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Life106Error {
+    InvalidLine(String), // Anything other than two integer coordinates
+    TooLarge,            // The cells span more than MAX_PATTERN_CELLS
+}
+
+impl fmt::Display for Life106Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Life106Error::InvalidLine(line) => write!(f, "expected 'x y' but found '{}'", line),
+            Life106Error::TooLarge => write!(
+                f,
+                "cells are spread over more than {} cells",
+                MAX_PATTERN_CELLS
+            ),
+        }
+    }
+}
+
+// Parses the Life 1.06 format: a `#Life 1.06` header, then one live cell per line as
+// `x y` coordinates relative to an arbitrary origin, with y growing downwards.
+//
+//     #Life 1.06
+//     0 -1
+//     1 0
+//     -1 1
+//     0 1
+//     1 1
+pub fn parse_life106(input: &str) -> Result<Vec<(i64, i64)>, Life106Error> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.split_whitespace().map(str::parse);
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+                _ => Err(Life106Error::InvalidLine(line.to_string())),
+            }
+        })
+        .collect()
+}
+
+// The smallest pattern containing every coordinate, so the origin itself is dropped. Cells
+// too far apart to fit in MAX_PATTERN_CELLS are an error.
+pub fn to_pattern(coordinates: &[(i64, i64)]) -> Result<Pattern, Life106Error> {
+    let Some(&(first_x, first_y)) = coordinates.first() else {
+        return Ok(Pattern {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        });
+    };
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (first_x, first_x, first_y, first_y);
+    for &(x, y) in coordinates {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_y = min_y.min(y);
+        max_y = max_y.max(y);
+    }
+
+    let extent = |min: i64, max: i64| {
+        let span = max.checked_sub(min)?.checked_add(1)?;
+        usize::try_from(span).ok()
+    };
+    let width = extent(min_x, max_x).ok_or(Life106Error::TooLarge)?;
+    let height = extent(min_y, max_y).ok_or(Life106Error::TooLarge)?;
+    let size = cell_count(width, height).ok_or(Life106Error::TooLarge)?;
+    let mut cells = vec![false; size];
+    for &(x, y) in coordinates {
+        cells[(y - min_y) as usize * width + (x - min_x) as usize] = true;
+    }
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+// Lists the pattern's live cells with its top-left corner as the origin
+pub fn to_life106(pattern: &Pattern) -> String {
+    let mut out = String::from("#Life 1.06\n");
+    for y in 0..pattern.height {
+        for x in 0..pattern.width {
+            if pattern.get(x, y) {
+                out.push_str(&format!("{} {}\n", x, y));
+            }
+        }
+    }
+    out
+}
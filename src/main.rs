@@ -1,272 +1,157 @@
 // Package: rusty_game_of_life_with_bevy
 // File: src/main.rs
 
-use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
-use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use rand::Rng;
-
-const GRID_SIZE: usize = 50; // Size of the grid (50x50)
-const CELL_SIZE: f32 = 10.0; // Size of each cell in pixels
-const TICK_RATE: f32 = 0.1; // Time in seconds between updates
-const CLICK_RADIUS: usize = 2; // Radius of influence for clicks
-
-#[derive(Resource)]
-struct Grid {
-    size: usize,
-    cells: Vec<bool>,
-    prev_cells: Vec<bool>, // Tracks the previous state of cells
-}
-
-impl Grid {
-    fn new(size: usize) -> Self {
-        let total_cells = size * size;
-        let cells = vec![false; total_cells]; // All cells start dead
-        let prev_cells = vec![false; total_cells];
-        Self {
-            size,
-            cells,
-            prev_cells,
-        }
-    }
-
-    fn get(&self, x: usize, y: usize) -> bool {
-        self.cells[y * self.size + x]
-    }
-
-    fn set(&mut self, x: usize, y: usize, value: bool) {
-        self.cells[y * self.size + x] = value;
-    }
+mod cli;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
 
-    fn toggle(&mut self, x: usize, y: usize) {
-        let index = y * self.size + x;
-        self.cells[index] = !self.cells[index];
+use bevy::prelude::*;
+use clap::Parser;
+use cli::Cli;
+use rusty_game_of_life_with_bevy::life106::{self, parse_life106};
+use rusty_game_of_life_with_bevy::patterns::parse_cells;
+use rusty_game_of_life_with_bevy::plugin::{window_plugin, GameConfig, MAX_GRID_SIZE};
+use rusty_game_of_life_with_bevy::recording::{parse_recording, Recording};
+use rusty_game_of_life_with_bevy::rle::parse_rle;
+use rusty_game_of_life_with_bevy::{GameOfLifePlugin, Pattern};
+use std::fs;
+
+// `config` with any options given on the command line. `--size n` sets both dimensions;
+// `--width` and `--height` override either one.
+fn config_from_cli(cli: &Cli, mut config: GameConfig) -> GameConfig {
+    if let Some(size) = cli.size {
+        config.grid_width = size;
+        config.grid_height = size;
     }
+    config.grid_width = cli.width.unwrap_or(config.grid_width);
+    config.grid_height = cli.height.unwrap_or(config.grid_height);
+    config.auto_pause_on_cycle |= cli.auto_pause;
+    config.infinite |= cli.infinite;
+    config.restart_on_empty |= cli.restart_on_empty;
+    config.fade_seconds = cli.fade_seconds.unwrap_or(config.fade_seconds);
+    config.history_limit = cli.history.unwrap_or(config.history_limit);
+    config.max_generations = cli.max_generations.or(config.max_generations);
+    config
+}
 
-    fn neighbors(&self, x: usize, y: usize) -> usize {
-        let mut count = 0;
-        for dx in -1..=1 {
-            for dy in -1..=1 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-                let nx = x as isize + dx;
-                let ny = y as isize + dy;
-                if nx >= 0
-                    && ny >= 0
-                    && nx < self.size as isize
-                    && ny < self.size as isize
-                    && self.get(nx as usize, ny as usize)
-                {
-                    count += 1;
-                }
-            }
-        }
-        count
+// Options given on the command line win over config.toml, which wins over the defaults
+fn plugin_from_cli(cli: &Cli) -> GameOfLifePlugin {
+    // The browser build has no config file to read
+    #[cfg(not(target_arch = "wasm32"))]
+    let defaults = config::load(config::CONFIG_PATH).apply(GameOfLifePlugin::default());
+    #[cfg(target_arch = "wasm32")]
+    let defaults = GameOfLifePlugin::default();
+
+    let mut config = config_from_cli(cli, defaults.config);
+    // The grid takes the size the recording was made at
+    let replay = cli.replay.as_deref().map(load_replay);
+    if let Some(recording) = &replay {
+        config.grid_width = recording.width;
+        config.grid_height = recording.height;
     }
-
-    fn step(&mut self) {
-        let mut new_cells = self.cells.clone();
-        for x in 0..self.size {
-            for y in 0..self.size {
-                let alive = self.get(x, y);
-                let neighbors = self.neighbors(x, y);
-                new_cells[y * self.size + x] = match (alive, neighbors) {
-                    (true, 2) | (_, 3) => true, // Stay alive or become alive
-                    _ => false,                 // Otherwise, die
-                };
-            }
-        }
-        self.prev_cells = self.cells.clone();
-        self.cells = new_cells;
+    GameOfLifePlugin {
+        start: cli
+            .pattern
+            .as_deref()
+            .map(|path| load_pattern(path, &config)),
+        replay,
+        record: cli.record.clone(),
+        config,
+        ruleset: cli.rule.unwrap_or(defaults.ruleset),
+        density: defaults.density,
+        theme: defaults.theme,
+        neighborhood: cli.neighborhood.unwrap_or(defaults.neighborhood),
+        cycle_window: cli.cycle_window.unwrap_or(defaults.cycle_window),
+        seed: cli.seed.unwrap_or(defaults.seed),
+        // Asking for a particular board means that board, not the last session's
+        restore_autosave: cli.seed.is_none() && cli.pattern.is_none() && cli.replay.is_none(),
     }
+}
 
-    fn get_color(&self, x: usize, y: usize) -> Color {
-        let current = self.cells[y * self.size + x];
-        let previous = self.prev_cells[y * self.size + x];
-        match (previous, current) {
-            (false, true) => Color::GREEN,  // Newly alive
-            (true, false) => Color::RED,    // Recently dead
-            (true, true) => Color::WHITE,   // Alive
-            (false, false) => Color::BLACK, // Dead
-        }
+// Picks the parser from the extension, falling back to the header and then to RLE
+fn parse_pattern(path: &str, contents: &str) -> Result<Pattern, String> {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    let life106 = || {
+        parse_life106(contents)
+            .and_then(|cells| life106::to_pattern(&cells))
+            .map_err(|err| err.to_string())
+    };
+    let rle = || parse_rle(contents).map_err(|err| err.to_string());
+    match extension.as_deref() {
+        Some("cells") => parse_cells(contents).map_err(|err| err.to_string()),
+        Some("lif" | "life") => life106(),
+        Some("rle") => rle(),
+        _ if contents.trim_start().starts_with("#Life 1.06") => life106(),
+        _ => rle(),
     }
 }
 
-#[derive(Resource)]
-struct TickTimer(Timer);
-
-fn setup(mut commands: Commands, mut grid: ResMut<Grid>, windows: Query<&Window, With<PrimaryWindow>>) {
-    info!("Setting up the game...");
-
-    // Spawn the camera
-    let camera_entity = commands.spawn(Camera2dBundle::default()).id();
-    info!("Camera spawned with entity ID: {:?}", camera_entity);
-
-    // Ensure the camera is properly aligned and scaled
-    if let Ok(window) = windows.get_single() {
-        commands.entity(camera_entity).insert(Transform {
-            scale: Vec3::new(
-                GRID_SIZE as f32 * CELL_SIZE / window.width(),
-                GRID_SIZE as f32 * CELL_SIZE / window.height(),
-                1.0,
-            ),
-            ..Default::default()
+// Reads the `--pattern glider.rle` file (RLE, Life 1.06 or plaintext `.cells`). There's
+// no sensible fallback for a pattern the user asked for, so problems end the program.
+fn load_pattern(path: &str, config: &GameConfig) -> Pattern {
+    let pattern = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| parse_pattern(path, &contents))
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to load pattern '{}': {}", path, err);
+            std::process::exit(1);
         });
+    if pattern.width > config.grid_width || pattern.height > config.grid_height {
+        eprintln!(
+            "Pattern '{}' is {}x{} but the grid is only {}x{}; pass a larger --size",
+            path, pattern.width, pattern.height, config.grid_width, config.grid_height
+        );
+        std::process::exit(1);
     }
-
-    // Create a random initial state for the grid
-    let mut rng = rand::thread_rng();
-    for x in 0..grid.size {
-        for y in 0..grid.size {
-            let alive = rng.gen_bool(0.2); // 20% chance for a cell to be alive
-            grid.set(x, y, alive);
-        }
-    }
-    info!("Initial grid state created with size: {}x{}", grid.size, grid.size);
-
-    // Add a visual border to outline the interactive grid area
-    let border_size = Vec2::new(GRID_SIZE as f32 * CELL_SIZE, GRID_SIZE as f32 * CELL_SIZE);
-    info!("Calculated border size: {:?}", border_size);
-
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: Color::rgba(1.0, 0.0, 0.0, 1.0), // Solid red border
-            custom_size: Some(border_size),
-            ..Default::default()
-        },
-        transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0)), // High z-value
-        ..Default::default()
-    });
-    info!("Border sprite spawned at position: Vec3(0.0, 0.0, 10.0)");
+    pattern
 }
 
-fn render_grid(grid: Res<Grid>, mut commands: Commands, query: Query<Entity, With<Sprite>>) {
-    // Despawn all previously rendered sprites
-    for entity in query.iter() {
-        commands.entity(entity).despawn();
-    }
-
-    // Render grid cells
-    for x in 0..grid.size {
-        for y in 0..grid.size {
-            let color = grid.get_color(x, y);
-            if color != Color::BLACK {
-                let cell_position = Vec3::new(
-                    x as f32 * CELL_SIZE - GRID_SIZE as f32 * CELL_SIZE / 2.0,
-                    y as f32 * CELL_SIZE - GRID_SIZE as f32 * CELL_SIZE / 2.0,
-                    0.0,
-                );
-
-                commands.spawn(SpriteBundle {
-                    sprite: Sprite {
-                        color,
-                        custom_size: Some(Vec2::splat(CELL_SIZE)),
-                        ..Default::default()
-                    },
-                    transform: Transform::from_translation(cell_position),
-                    ..Default::default()
-                });
-            }
-        }
+// Reads the frames of the `--replay run.rec` recording
+fn load_replay(path: &str) -> Recording {
+    let recording = fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| parse_recording(&bytes).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to load recording '{}': {}", path, err);
+            std::process::exit(1);
+        });
+    if recording.width > MAX_GRID_SIZE || recording.height > MAX_GRID_SIZE {
+        eprintln!(
+            "Recording '{}' is {}x{}, larger than the {} cell limit",
+            path, recording.width, recording.height, MAX_GRID_SIZE
+        );
+        std::process::exit(1);
     }
+    recording
 }
 
-fn handle_clicks(
-    buttons: Res<Input<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut grid: ResMut<Grid>,
-) {
-    if buttons.just_pressed(MouseButton::Left) {
-        if let Ok(window) = windows.get_single() {
-            if let Some(cursor_position) = window.cursor_position() {
-                let grid_x = ((cursor_position.x / window.width()) * GRID_SIZE as f32).floor() as isize;
-                let grid_y = ((1.0 - cursor_position.y / window.height()) * GRID_SIZE as f32).floor() as isize;
+fn main() {
+    let cli = Cli::parse();
+    let plugin = plugin_from_cli(&cli);
+    plugin.print_settings();
 
-                // Ensure the click is within the grid boundaries
-                if grid_x >= 0
-                    && grid_y >= 0
-                    && grid_x < GRID_SIZE as isize
-                    && grid_y < GRID_SIZE as isize
-                {
-                    for dx in -(CLICK_RADIUS as isize)..=(CLICK_RADIUS as isize) {
-                        for dy in -(CLICK_RADIUS as isize)..=(CLICK_RADIUS as isize) {
-                            let nx = grid_x + dx;
-                            let ny = grid_y + dy;
-                            if nx >= 0
-                                && ny >= 0
-                                && nx < GRID_SIZE as isize
-                                && ny < GRID_SIZE as isize
-                            {
-                                grid.toggle(nx as usize, ny as usize);
-                            }
-                        }
-                    }
-                    info!("Clicked grid position: ({}, {})", grid_x, grid_y);
-                } else {
-                    info!(
-                        "Click outside grid bounds: ({}, {}), Grid size: {}",
-                        grid_x, grid_y, GRID_SIZE
-                    );
-                }
-            }
-        }
+    if let Some(generations) = cli.headless {
+        plugin.run_headless(generations);
+        return;
     }
-}
 
-fn setup_fps_counter(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Text2dBundle {
-        text: Text::from_section(
-            "FPS: 0".to_string(),
-            TextStyle {
-                font: asset_server.load("fonts/UniversCondensed.ttf"),
-                font_size: 20.0,
-                color: Color::WHITE,
-            },
-        ),
-        transform: Transform::from_translation(Vec3::new(-250.0, 200.0, 20.0)), // Ensure z-value is above the border
-        ..Default::default()
-    });
-}
-
-fn update_fps_counter(diagnostics: Res<DiagnosticsStore>, mut query: Query<&mut Text>) {
-    if let Some(fps) = diagnostics
-        .get(FrameTimeDiagnosticsPlugin::FPS)
-        .and_then(|d| d.average())
-    {
-        for mut text in query.iter_mut() {
-            text.sections[0].value = format!("FPS: {:.0}", fps);
+    if plugin.config.infinite {
+        // The sparse world only stores live cells, so there's nowhere to keep dying ones
+        if plugin.ruleset.states > 2 {
+            eprintln!(
+                "--infinite can't run Generations rules such as {}; pick a two-state rule",
+                plugin.ruleset
+            );
+            std::process::exit(1);
         }
+        plugin.run_infinite();
+        return;
     }
-}
-
-fn update_grid(time: Res<Time>, mut timer: ResMut<TickTimer>, mut grid: ResMut<Grid>) {
-    if timer.0.tick(time.delta()).just_finished() {
-        grid.step();
-    }
-}
 
-fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Rusty Game of Life".to_string(),
-                resolution: (1000.0, 720.0).into(), // Set the initial window size
-                ..Default::default()
-            }),
-            ..Default::default()
-        }))
-        .add_plugins(FrameTimeDiagnosticsPlugin)
-        .insert_resource(Grid::new(GRID_SIZE))
-        .insert_resource(TickTimer(Timer::from_seconds(
-            TICK_RATE,
-            TimerMode::Repeating,
-        )))
-        .add_systems(Startup, setup)
-        .add_systems(Startup, setup_fps_counter)
-        .add_systems(Update, update_grid)
-        .add_systems(Update, render_grid)
-        .add_systems(Update, handle_clicks)
-        .add_systems(Update, update_fps_counter)
+        .add_plugins(DefaultPlugins.set(window_plugin()))
+        .add_plugins(plugin)
         .run();
-}
\ No newline at end of file
+}
@@ -0,0 +1,122 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/patterns.rs
+
+use crate::rle::parse_rle;
+use std::fmt;
+
+// A rectangular block of cells, row 0 being the top row as written in pattern files
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<bool>,
+}
+
+impl Pattern {
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.width + x]
+    }
+}
+
+// Most cells a parsed pattern may have, so a file claiming a huge size is rejected before its
+// cells are allocated. The largest grid the frontend allows is a million cells.
+pub const MAX_PATTERN_CELLS: usize = 1 << 24;
+
+// width * height, if that's within MAX_PATTERN_CELLS
+pub(crate) fn cell_count(width: usize, height: usize) -> Option<usize> {
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_PATTERN_CELLS)
+}
+
+// Well-known patterns by name, parsed from the RLE files in `assets/patterns` that are
+// embedded in the binary
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct PatternLibrary {
+    pub patterns: Vec<(&'static str, Pattern)>,
+}
+
+const BUILTIN_RLE: [(&str, &str); 8] = [
+    ("glider", include_str!("../assets/patterns/glider.rle")),
+    ("LWSS", include_str!("../assets/patterns/lwss.rle")),
+    ("blinker", include_str!("../assets/patterns/blinker.rle")),
+    ("toad", include_str!("../assets/patterns/toad.rle")),
+    ("pulsar", include_str!("../assets/patterns/pulsar.rle")),
+    (
+        "Gosper glider gun",
+        include_str!("../assets/patterns/gosper_glider_gun.rle"),
+    ),
+    ("MWSS", include_str!("../assets/patterns/mwss.rle")),
+    ("HWSS", include_str!("../assets/patterns/hwss.rle")),
+];
+
+impl PatternLibrary {
+    // The embedded files are checked by the tests, so a parse failure here is a bug
+    pub fn builtin() -> Self {
+        let patterns = BUILTIN_RLE
+            .iter()
+            .map(|&(name, rle)| match parse_rle(rle) {
+                Ok(pattern) => (name, pattern),
+                Err(err) => panic!("built-in pattern {} is invalid: {}", name, err),
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Pattern> {
+        self.patterns
+            .iter()
+            .find(|(entry, _)| entry.eq_ignore_ascii_case(name))
+            .map(|(_, pattern)| pattern)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CellsError {
+    TooLarge(usize, usize), // The rows' width and height, over MAX_PATTERN_CELLS
+}
+
+impl fmt::Display for CellsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CellsError::TooLarge(width, height) => write!(
+                f,
+                "{}x{} is larger than the {} cell limit",
+                width, height, MAX_PATTERN_CELLS
+            ),
+        }
+    }
+}
+
+// Parses the plaintext `.cells` format: `!` starts a comment line, `O` is alive and
+// anything else (normally `.`) is dead. Short rows are padded with dead cells.
+pub fn parse_cells(input: &str) -> Result<Pattern, CellsError> {
+    let mut rows: Vec<&str> = input
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.starts_with('!'))
+        .collect();
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let width = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = rows.len();
+    let size = cell_count(width, height).ok_or(CellsError::TooLarge(width, height))?;
+    let mut cells = vec![false; size];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            cells[y * width + x] = c == 'O';
+        }
+    }
+
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
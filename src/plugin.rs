@@ -0,0 +1,2564 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/plugin.rs
+
+use crate::colours::{self, Theme};
+use crate::grid::CYCLE_WINDOW;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::recording;
+use crate::recording::Recording;
+use crate::{
+    CellState, Grid, History, Life, Neighborhood, Pattern, PatternLibrary, Ruleset, SparseGrid,
+    Topology,
+};
+use bevy::app::AppExit;
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy::window::PrimaryWindow;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+#[cfg(not(target_arch = "wasm32"))]
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+use std::ops::RangeInclusive;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const GRID_SIZE: usize = 50; // Default width and height of the grid (50x50)
+pub const MAX_GRID_SIZE: usize = 1000; // Upper bound for each dimension to keep sprite counts sane
+const CELL_SIZE: f32 = 10.0; // Size of each cell in pixels
+const TICK_RATE: f32 = 0.1; // Time in seconds between updates
+const CLICK_RADIUS: usize = 2; // Default brush radius for painting and erasing
+const MAX_BRUSH_RADIUS: usize = 10; // Largest radius `.` grows the brush to
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_PATH: &str = "save.gol"; // File used by the save/load hotkeys
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_PATH: &str = "export.rle"; // File written by the RLE export hotkey
+#[cfg(not(target_arch = "wasm32"))]
+const AUTOSAVE_FILE: &str = "autosave.json"; // Board kept across restarts, next to the binary
+#[cfg(not(target_arch = "wasm32"))]
+const PNG_CELL_PIXELS: u32 = 4; // Side of each cell's square in PNG exports
+#[cfg(not(target_arch = "wasm32"))]
+const GIF_CELL_PIXELS: u32 = 2; // Side of each cell's square in GIF frames
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_GIF_BYTES: usize = 256 << 20; // Frame memory held before a GIF recording stops itself
+const HEAT_AGE_CAP: u32 = 50; // Age at which the heatmap reaches full red
+const HISTORY_LIMIT: usize = 100; // Default number of undo snapshots kept
+pub const MAX_HISTORY_LIMIT: usize = 10_000; // Upper bound for --history
+pub const MAX_CYCLE_WINDOW: usize = 1000; // Upper bound for --cycle-window
+pub const MIN_TICK_RATE: f32 = 0.01; // Fastest allowed time between updates
+pub const MAX_TICK_RATE: f32 = 2.0; // Slowest allowed time between updates
+const PAN_SPEED: f32 = 300.0; // Keyboard pan speed in world units per second at zoom 1
+const RESIZE_STEP: usize = 10; // Cells added or removed per resize keypress
+const GRID_LINE_WIDTH: f32 = 1.0; // Thickness of the optional grid lines in world units
+const FADE_SECONDS: f32 = 0.5; // Default time for a dead cell to fade out
+const MIN_ZOOM: f32 = 0.1; // Smallest camera projection scale (most zoomed in)
+const MAX_ZOOM: f32 = 4.0; // Largest camera projection scale (most zoomed out)
+const MINIMAP_SIZE: f32 = 200.0; // Longer side of the minimap on screen, in pixels
+const MINIMAP_MAX_PIXELS: usize = 200; // Most image pixels along the minimap's longer side
+const MINIMAP_REFRESH: f32 = 0.25; // Seconds between minimap redraws
+
+// Startup settings, defaulting to the constants above
+#[derive(Resource, Clone, Debug)]
+pub struct GameConfig {
+    pub grid_width: usize,
+    pub grid_height: usize,
+    pub cell_size: f32,
+    pub tick_rate: f32,
+    pub auto_pause_on_cycle: bool, // Pause once the board repeats itself
+    pub infinite: bool,            // Step an unbounded sparse world instead of the grid
+    pub history_limit: usize,      // Snapshots kept for undo and rewinding
+    pub restart_on_empty: bool,    // Re-randomize as soon as the population dies out
+    pub fade_seconds: f32,         // How long recently dead cells take to fade out
+    pub max_generations: Option<u64>, // Stop stepping once the board reaches this generation
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            grid_width: GRID_SIZE,
+            grid_height: GRID_SIZE,
+            cell_size: CELL_SIZE,
+            tick_rate: TICK_RATE,
+            auto_pause_on_cycle: false,
+            infinite: false,
+            history_limit: HISTORY_LIMIT,
+            restart_on_empty: false,
+            fade_seconds: FADE_SECONDS,
+            max_generations: None,
+        }
+    }
+}
+
+impl GameConfig {
+    // Clearing or randomizing resets the generation, so the limit applies afresh
+    fn reached_max_generations(&self, grid: &Grid) -> bool {
+        self.max_generations
+            .is_some_and(|max| grid.generation >= max)
+    }
+
+    // Size of the whole grid in world units
+    fn extent(&self) -> Vec2 {
+        Vec2::new(self.grid_width as f32, self.grid_height as f32) * self.cell_size
+    }
+}
+
+#[derive(Resource)]
+struct TickTimer(Timer);
+
+// Sent whenever the grid's size changes, so the board entities can be rebuilt
+#[derive(Event)]
+struct GridResized;
+
+#[derive(Component)]
+struct MainCamera;
+
+#[derive(Component)]
+struct Border;
+
+// Parent of the grid line sprites, so a single Visibility toggles them all
+#[derive(Component)]
+struct GridLines;
+
+#[derive(Component)]
+struct FpsText;
+
+#[derive(Component)]
+struct PopulationText;
+
+#[derive(Component)]
+struct GenerationText;
+
+#[derive(Component)]
+struct TickRateText;
+
+#[derive(Component)]
+struct StatusText;
+
+#[derive(Component)]
+struct BrushText;
+
+#[derive(Component)]
+struct BoundsText;
+
+// The minimap image in the bottom-right corner, and the outline of the view on top of it
+#[derive(Component)]
+struct Minimap;
+
+#[derive(Component)]
+struct MinimapViewport;
+
+#[derive(Resource)]
+struct MinimapTimer(Timer);
+
+#[derive(Component)]
+struct CellMarker {
+    x: usize,
+    y: usize,
+}
+
+// How far through fading out a dead cell is, from 1 when it has just died down to 0
+#[derive(Component, Default)]
+struct CellFade(f32);
+
+// Whether recently dead cells fade out over `GameConfig::fade_seconds` rather than
+// showing the theme's death colour for exactly one generation; toggled with F
+#[derive(Resource)]
+struct FadeDeadCells(bool);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BrushShape {
+    Square,
+    Circle,
+}
+
+// Settings for painting with the mouse
+#[derive(Resource)]
+struct Brush {
+    radius: usize, // Cells painted on each side of the cursor
+    shape: BrushShape,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            radius: CLICK_RADIUS,
+            shape: BrushShape::Square,
+        }
+    }
+}
+
+impl Brush {
+    // Whether the cell at offset (dx, dy) from the cursor is under the brush
+    fn covers(&self, dx: isize, dy: isize) -> bool {
+        let radius = self.radius as isize;
+        match self.shape {
+            BrushShape::Square => dx.abs() <= radius && dy.abs() <= radius,
+            BrushShape::Circle => dx * dx + dy * dy <= radius * radius,
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("Brush: {} ({:?})", self.radius, self.shape)
+    }
+}
+
+// While on (toggled with I), number keys pick a pattern instead of the brush radius and
+// left clicks stamp it instead of painting
+#[derive(Resource, Default)]
+struct InsertMode(bool);
+
+// Index into the PatternLibrary of the pattern stamped in insert mode
+#[derive(Resource, Default)]
+struct SelectedPattern(usize);
+
+// Translucent outline of the brush under the cursor, one entity per shape
+#[derive(Component)]
+struct BrushPreview(BrushShape);
+
+#[derive(Resource)]
+struct RandomConfig {
+    density: f64, // Chance for each cell to start alive
+}
+
+impl Default for RandomConfig {
+    fn default() -> Self {
+        Self { density: 0.2 }
+    }
+}
+
+// Seed behind RngResource, kept so a run can be replayed with --seed
+#[derive(Resource, Clone, Copy, Debug)]
+struct RngSeed(u64);
+
+#[derive(Resource)]
+struct RngResource(StdRng);
+
+// Pattern from --pattern, stamped at startup in place of the random board
+#[derive(Resource)]
+struct StartPattern(Option<Pattern>);
+
+// Whether `setup` starts from the board saved when the last session closed
+#[derive(Resource)]
+struct RestoreAutosave(bool);
+
+// Frames from --replay, shown at the tick rate in place of stepping the grid
+#[derive(Resource)]
+struct Replay {
+    recording: Recording,
+    frame: usize,
+}
+
+// File from --record, opened by `start_recording`
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct RecordPath(String);
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct Recorder {
+    file: fs::File,
+    size: (usize, usize),         // Frames must all match the header
+    last_generation: Option<u64>, // Generation of the frame written last
+}
+
+// Frames captured since V started a GIF recording, held in memory until it stops
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource, Default)]
+struct GifCapture {
+    frames: Option<Vec<image::RgbaImage>>, // None while not recording
+    size: (usize, usize),                  // Grid size when the recording started
+    last_generation: Option<u64>,
+}
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+enum SimState {
+    Running,
+    Paused,
+}
+
+// How cells are coloured, toggled with H
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ColorMode {
+    #[default]
+    StateDiff, // Births green, deaths red, survivors white
+    HeatAge, // Live cells by how long they've survived
+}
+
+#[allow(clippy::too_many_arguments)]
+fn setup(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    random: Res<RandomConfig>,
+    seed: Res<RngSeed>,
+    mut rng: ResMut<RngResource>,
+    start: Res<StartPattern>,
+    restore: Res<RestoreAutosave>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    mut grid: ResMut<Grid>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    info!("Setting up the game...");
+    spawn_camera(&mut commands, &config, windows.get_single().ok());
+
+    // A pattern to stamp always wins over the board from the last session
+    let restored = restore.0 && start.0.is_none() && restore_autosave(&mut grid);
+    if !restored {
+        seed_board(&mut grid, start.0.as_ref(), random.density, &mut rng.0);
+        if start.0.is_none() {
+            info!("Random seed: {}", seed.0);
+        }
+    }
+    info!("Rule: {}", grid.ruleset);
+    info!(
+        "Initial grid state created with size: {}x{}",
+        grid.width, grid.height
+    );
+
+    spawn_cells(&mut commands, &config, &grid, *mode, &theme);
+    spawn_border(&mut commands, &config);
+    spawn_grid_lines(&mut commands, &config, Visibility::Hidden);
+}
+
+fn spawn_camera(commands: &mut Commands, config: &GameConfig, window: Option<&Window>) {
+    let camera_entity = commands.spawn((Camera2dBundle::default(), MainCamera)).id();
+    info!("Camera spawned with entity ID: {:?}", camera_entity);
+
+    // Ensure the camera is properly aligned and scaled. It keeps the default 2D camera
+    // depth so sprites with a positive z stay in front of its near plane.
+    if let Some(window) = window {
+        commands.entity(camera_entity).insert(Transform {
+            translation: Vec3::new(0.0, 0.0, 999.9),
+            scale: camera_fit_scale(config, window),
+            ..Default::default()
+        });
+    }
+}
+
+// Starts from the pattern if one was given and it fits, otherwise from a random board
+fn seed_board(grid: &mut Grid, start: Option<&Pattern>, density: f64, rng: &mut StdRng) {
+    match start {
+        Some(pattern) if pattern.width <= grid.width && pattern.height <= grid.height => {
+            let x = (grid.width - pattern.width) / 2;
+            let y = (grid.height - pattern.height) / 2 + pattern.height - 1; // Top row
+            grid.insert_pattern(x, y, pattern);
+            info!("Pattern stamped at ({}, {})", x, y);
+        }
+        Some(pattern) => {
+            warn!(
+                "The {}x{} pattern doesn't fit the {}x{} grid, starting from a random board",
+                pattern.width, pattern.height, grid.width, grid.height
+            );
+            grid.randomize(density, rng);
+        }
+        None => grid.randomize(density, rng),
+    }
+}
+
+// The board as written to AUTOSAVE_FILE, with a row per line of `O` and `.` as in `save.gol`
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Serialize, Deserialize)]
+struct Autosave {
+    size: (usize, usize),
+    generation: u64,
+    rows: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Autosave {
+    fn of(grid: &Grid) -> Self {
+        let rows = (0..grid.height)
+            .map(|y| {
+                let row = (0..grid.width).map(|x| if grid.get(x, y) { 'O' } else { '.' });
+                row.collect()
+            })
+            .collect();
+        Self {
+            size: (grid.width, grid.height),
+            generation: grid.generation,
+            rows,
+        }
+    }
+
+    // Checks the whole file before touching the grid, so a bad one leaves it as it was
+    fn restore(&self, grid: &mut Grid) -> Result<(), String> {
+        if self.size != (grid.width, grid.height) {
+            let (width, height) = self.size;
+            return Err(format!(
+                "it is {}x{} but the grid is {}x{}",
+                width, height, grid.width, grid.height
+            ));
+        }
+        if self.rows.len() != grid.height {
+            let found = self.rows.len();
+            return Err(format!("expected {} rows, found {}", grid.height, found));
+        }
+        let mut cells = Vec::with_capacity(grid.width * grid.height);
+        for (y, row) in self.rows.iter().enumerate() {
+            if row.chars().count() != grid.width {
+                return Err(format!("row {} is not {} cells wide", y, grid.width));
+            }
+            for c in row.chars() {
+                match c {
+                    'O' => cells.push(true),
+                    '.' => cells.push(false),
+                    _ => return Err(format!("unexpected character '{}'", c)),
+                }
+            }
+        }
+        grid.clear();
+        for (index, alive) in cells.into_iter().enumerate() {
+            if alive {
+                grid.set(index % grid.width, index / grid.width, true);
+            }
+        }
+        grid.prev_cells.copy_from_slice(&grid.cells);
+        grid.generation = self.generation;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn autosave_path() -> Option<PathBuf> {
+    Some(std::env::current_exe().ok()?.with_file_name(AUTOSAVE_FILE))
+}
+
+// Loads the board saved when the app last closed, if it is there and fits the grid. Any
+// other file is reported and ignored, and the board is randomized as usual.
+#[cfg(not(target_arch = "wasm32"))]
+fn restore_autosave(grid: &mut Grid) -> bool {
+    let Some(path) = autosave_path() else {
+        return false;
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return false; // Nothing saved yet
+    };
+    let restored = serde_json::from_str::<Autosave>(&contents)
+        .map_err(|err| err.to_string())
+        .and_then(|save| save.restore(grid));
+    match restored {
+        Ok(()) => {
+            info!("Board restored from {}", path.display());
+            true
+        }
+        Err(err) => {
+            warn!("Ignoring {}: {}", path.display(), err);
+            false
+        }
+    }
+}
+
+// Browsers have no filesystem to autosave into
+#[cfg(target_arch = "wasm32")]
+fn restore_autosave(_grid: &mut Grid) -> bool {
+    false
+}
+
+// Runs in `Last`, after the window-closing systems in Update have sent AppExit
+#[cfg(not(target_arch = "wasm32"))]
+fn autosave_on_exit(mut exit: EventReader<AppExit>, grid: Res<Grid>) {
+    if exit.iter().count() == 0 {
+        return;
+    }
+    let Some(path) = autosave_path() else {
+        error!("Can't find the binary's directory to autosave into");
+        return;
+    };
+    let written = serde_json::to_string(&Autosave::of(&grid))
+        .map_err(|err| err.to_string())
+        .and_then(|json| fs::write(&path, json).map_err(|err| err.to_string()));
+    match written {
+        Ok(()) => info!("Board saved to {}", path.display()),
+        Err(err) => error!("Failed to autosave to {}: {}", path.display(), err),
+    }
+}
+
+// Camera scale that fits the whole grid to the window, each axis independently
+fn camera_fit_scale(config: &GameConfig, window: &Window) -> Vec3 {
+    let extent = config.extent();
+    Vec3::new(extent.x / window.width(), extent.y / window.height(), 1.0)
+}
+
+// Spawn one persistent sprite per cell; render_grid only recolours them
+fn spawn_cells(
+    commands: &mut Commands,
+    config: &GameConfig,
+    grid: &Grid,
+    mode: ColorMode,
+    theme: &Theme,
+) {
+    for x in 0..grid.width {
+        for y in 0..grid.height {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: display_color(mode, theme, grid, x, y),
+                        custom_size: Some(Vec2::splat(config.cell_size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(cell_position(config, x, y)),
+                    ..Default::default()
+                },
+                CellMarker { x, y },
+                CellFade::default(),
+            ));
+        }
+    }
+    info!("Spawned {} cell sprites", grid.width * grid.height);
+}
+
+fn spawn_border(commands: &mut Commands, config: &GameConfig) {
+    // Add a visual border to outline the interactive grid area. It now persists,
+    // so it sits behind the cells and is padded by a cell on each side.
+    let border_size = config.extent() + Vec2::splat(2.0 * config.cell_size);
+    info!("Calculated border size: {:?}", border_size);
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 0.0, 0.0, 1.0), // Solid red border
+                custom_size: Some(border_size),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, -1.0)), // Behind the cells
+            ..Default::default()
+        },
+        Border,
+    ));
+    info!("Border sprite spawned at position: Vec3(0.0, 0.0, -1.0)");
+}
+
+// Thin sprites along every cell boundary, hidden until toggled with G. They sit above the
+// cells (z = 0) but below the HUD, which is UI and always drawn on top.
+fn spawn_grid_lines(commands: &mut Commands, config: &GameConfig, visibility: Visibility) {
+    let extent = config.extent();
+    let line = |size: Vec2, position: Vec2| SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgba(0.5, 0.5, 0.5, 0.5),
+            custom_size: Some(size),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(position.extend(1.0)),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            SpatialBundle {
+                visibility,
+                ..Default::default()
+            },
+            GridLines,
+        ))
+        .with_children(|parent| {
+            for i in 0..=config.grid_width {
+                let offset = i as f32 * config.cell_size - extent.x / 2.0;
+                parent.spawn(line(
+                    Vec2::new(GRID_LINE_WIDTH, extent.y),
+                    Vec2::new(offset, 0.0),
+                ));
+            }
+            for i in 0..=config.grid_height {
+                let offset = i as f32 * config.cell_size - extent.y / 2.0;
+                parent.spawn(line(
+                    Vec2::new(extent.x, GRID_LINE_WIDTH),
+                    Vec2::new(0.0, offset),
+                ));
+            }
+        });
+}
+
+fn toggle_grid_lines(
+    keys: Res<Input<KeyCode>>,
+    mut query: Query<&mut Visibility, With<GridLines>>,
+) {
+    if keys.just_pressed(KeyCode::G) {
+        for mut visibility in query.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+// Ctrl+] grows the grid and Ctrl+[ shrinks it, by the same amount along both axes
+fn resize_grid(
+    keys: Res<Input<KeyCode>>,
+    mut grid: ResMut<Grid>,
+    mut resized: EventWriter<GridResized>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if !keys.any_just_pressed([KeyCode::BracketLeft, KeyCode::BracketRight]) {
+        return;
+    }
+    let resize = |size: usize| {
+        let size = if keys.just_pressed(KeyCode::BracketRight) {
+            size + RESIZE_STEP
+        } else {
+            size.saturating_sub(RESIZE_STEP)
+        };
+        size.clamp(RESIZE_STEP, MAX_GRID_SIZE)
+    };
+    let (width, height) = (resize(grid.width), resize(grid.height));
+    if (width, height) != (grid.width, grid.height) {
+        grid.resize(width, height);
+        resized.send(GridResized);
+        info!("Grid resized to {}x{}", width, height);
+    }
+}
+
+// Respawns everything whose layout depends on the grid size once the grid has been resized
+#[allow(clippy::too_many_arguments)]
+fn rebuild_board(
+    mut commands: Commands,
+    mut resized: EventReader<GridResized>,
+    mut config: ResMut<GameConfig>,
+    grid: Res<Grid>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    mut history: ResMut<History>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+    board: Query<Entity, Or<(With<CellMarker>, With<Border>)>>,
+    grid_lines: Query<(Entity, &Visibility), With<GridLines>>,
+) {
+    if resized.iter().count() == 0 {
+        return;
+    }
+    config.grid_width = grid.width;
+    config.grid_height = grid.height;
+    // Snapshots of the old size can't be restored into the new grid
+    history.clear();
+
+    for entity in board.iter() {
+        commands.entity(entity).despawn();
+    }
+    let mut lines_visibility = Visibility::Hidden;
+    for (entity, visibility) in grid_lines.iter() {
+        lines_visibility = *visibility;
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_cells(&mut commands, &config, &grid, *mode, &theme);
+    spawn_border(&mut commands, &config);
+    spawn_grid_lines(&mut commands, &config, lines_visibility);
+
+    if let (Ok(window), Ok(mut transform)) = (windows.get_single(), cameras.get_single_mut()) {
+        transform.scale = camera_fit_scale(&config, window);
+    }
+}
+
+// World-space centre of the sprite for cell (x, y), with the grid centred on the origin
+fn cell_position(config: &GameConfig, x: usize, y: usize) -> Vec3 {
+    world_cell_position(config, x as i64, y as i64)
+}
+
+// Same layout for the infinite world, whose cells run past the grid in every direction
+fn world_cell_position(config: &GameConfig, x: i64, y: i64) -> Vec3 {
+    let half_extent = config.extent() / 2.0;
+    Vec3::new(
+        x as f32 * config.cell_size - half_extent.x + config.cell_size / 2.0,
+        y as f32 * config.cell_size - half_extent.y + config.cell_size / 2.0,
+        0.0,
+    )
+}
+
+// The (possibly out of range) cell under the cursor. Going through the camera keeps the
+// mapping right whatever its scale or position.
+fn cursor_cell(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    config: &GameConfig,
+) -> Option<(isize, isize)> {
+    let world_position = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))?;
+    Some(world_to_cell(config, world_position))
+}
+
+fn on_grid(grid: &Grid, x: isize, y: isize) -> bool {
+    x >= 0 && y >= 0 && x < grid.width as isize && y < grid.height as isize
+}
+
+// Inverse of `cell_position`: the (possibly out of range) cell containing a world point
+fn world_to_cell(config: &GameConfig, world_position: Vec2) -> (isize, isize) {
+    let half_extent = config.extent() / 2.0;
+    (
+        ((world_position.x + half_extent.x) / config.cell_size).floor() as isize,
+        ((world_position.y + half_extent.y) / config.cell_size).floor() as isize,
+    )
+}
+
+// Young cells are blue, shading to red as they approach HEAT_AGE_CAP generations
+fn heat_color(age: u32) -> Color {
+    let heat = age.min(HEAT_AGE_CAP) as f32 / HEAT_AGE_CAP as f32;
+    Color::rgb(heat, 0.0, 1.0 - heat)
+}
+
+// Dying cells of a Generations rule fade from the theme's dying colour towards dead. Their
+// live cells are all drawn alike, since under rules like Brian's Brain every one is newborn.
+fn display_color(mode: ColorMode, theme: &Theme, grid: &Grid, x: usize, y: usize) -> Color {
+    let state = grid.state(x, y);
+    let generations = grid.ruleset.states > 2;
+    match mode {
+        ColorMode::StateDiff if generations && state > 1 => {
+            let stages = (grid.ruleset.states - 2) as f32;
+            colours::mix(theme.dying, theme.dead, (state - 2) as f32 / stages)
+        }
+        ColorMode::StateDiff if generations && state == 1 => theme.alive,
+        ColorMode::StateDiff => theme.color(grid.cell_state(x, y)),
+        ColorMode::HeatAge if grid.get(x, y) => heat_color(grid.cell_age(x, y)),
+        ColorMode::HeatAge => theme.dead,
+    }
+}
+
+// The cells in the camera's view, plus a cell of margin on each side so sprites scrolling
+// in are already coloured. Reads the camera's Transform rather than its GlobalTransform,
+// which isn't propagated until after Update and would lag a frame behind any pan.
+fn visible_cells(
+    window: &Window,
+    transform: &Transform,
+    projection: &OrthographicProjection,
+    config: &GameConfig,
+) -> (RangeInclusive<isize>, RangeInclusive<isize>) {
+    let window_size = Vec2::new(window.width(), window.height());
+    let half_view = window_size / 2.0 * projection.scale * transform.scale.truncate();
+    let centre = transform.translation.truncate();
+    let (min_x, min_y) = world_to_cell(config, centre - half_view);
+    let (max_x, max_y) = world_to_cell(config, centre + half_view);
+    (min_x - 1..=max_x + 1, min_y - 1..=max_y + 1)
+}
+
+// Only cells in view are recoloured; the rest are hidden so zoomed-in views of large grids
+// stay cheap to draw. Panning or zooming re-culls even when the grid hasn't changed.
+#[allow(clippy::too_many_arguments)]
+fn render_grid(
+    grid: Res<Grid>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut last_view: Local<Option<(RangeInclusive<isize>, RangeInclusive<isize>)>>,
+    mut query: Query<(&CellMarker, &mut Sprite, &mut Visibility)>,
+) {
+    let (Ok(window), Ok((transform, projection))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let view = visible_cells(window, transform, projection, &config);
+    let moved = last_view.as_ref() != Some(&view);
+    if !moved && !grid.is_changed() && !mode.is_changed() && !theme.is_changed() {
+        return;
+    }
+    let (xs, ys) = &view;
+    for (cell, mut sprite, mut visibility) in query.iter_mut() {
+        if xs.contains(&(cell.x as isize)) && ys.contains(&(cell.y as isize)) {
+            visibility.set_if_neq(Visibility::Inherited);
+            sprite.color = display_color(*mode, &theme, &grid, cell.x, cell.y);
+        } else {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+    *last_view = Some(view);
+}
+
+// Runs every frame rather than on each step, so a fade stays smooth however slow the tick
+// rate. Runs after `render_grid` and overrides its colour for cells still fading.
+#[allow(clippy::too_many_arguments)]
+fn fade_dead_cells(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    fade: Res<FadeDeadCells>,
+    grid: Res<Grid>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    mut last_generation: Local<Option<u64>>,
+    mut query: Query<(&CellMarker, &mut CellFade, &mut Sprite, &Visibility)>,
+) {
+    if !fade.0 || *mode != ColorMode::StateDiff {
+        // Put back the colours of any cells left part-way through fading
+        if fade.is_changed() || mode.is_changed() {
+            for (cell, mut fading, mut sprite, _) in query.iter_mut() {
+                if fading.0 > 0.0 {
+                    fading.0 = 0.0;
+                    sprite.color = display_color(*mode, &theme, &grid, cell.x, cell.y);
+                }
+            }
+        }
+        return;
+    }
+
+    // Only steps start a fade; an edit elsewhere shouldn't restart ones in progress
+    let stepped = *last_generation != Some(grid.generation);
+    *last_generation = Some(grid.generation);
+    let decay = time.delta_seconds() / config.fade_seconds;
+    for (cell, mut fading, mut sprite, visibility) in query.iter_mut() {
+        // Dying cells already show their own stages
+        if stepped {
+            match grid.cell_state(cell.x, cell.y) {
+                _ if grid.state(cell.x, cell.y) > 1 => fading.0 = 0.0,
+                CellState::RecentlyDead => fading.0 = 1.0,
+                CellState::Dead => {}
+                _ => fading.0 = 0.0,
+            }
+        }
+        if fading.0 <= 0.0 {
+            continue;
+        }
+        fading.0 = (fading.0 - decay).max(0.0);
+        if *visibility != Visibility::Hidden {
+            sprite.color = colours::mix(theme.dead, theme.recently_dead, fading.0);
+        }
+    }
+}
+
+fn toggle_fade(keys: Res<Input<KeyCode>>, mut fade: ResMut<FadeDeadCells>) {
+    if keys.just_pressed(KeyCode::F) {
+        fade.0 = !fade.0;
+        info!("Fading dead cells: {}", if fade.0 { "on" } else { "off" });
+    }
+}
+
+fn cycle_theme(keys: Res<Input<KeyCode>>, mut theme: ResMut<Theme>) {
+    if keys.just_pressed(KeyCode::T) {
+        *theme = theme.next();
+        info!("Theme: {}", theme.name);
+    }
+}
+
+fn toggle_color_mode(keys: Res<Input<KeyCode>>, mut mode: ResMut<ColorMode>) {
+    if keys.just_pressed(KeyCode::H) {
+        *mode = match *mode {
+            ColorMode::StateDiff => ColorMode::HeatAge,
+            ColorMode::HeatAge => ColorMode::StateDiff,
+        };
+        info!("Colour mode: {:?}", *mode);
+    }
+}
+
+// Only cell sprites are ever touched by rendering; catch any regression that drops the border
+fn assert_border_persists(border: Query<(), With<Border>>) {
+    debug_assert!(!border.is_empty(), "border sprite was despawned");
+}
+
+// Hold the left button to paint cells alive, or the right button to erase them
+#[allow(clippy::too_many_arguments)]
+fn handle_clicks(
+    buttons: Res<Input<MouseButton>>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    brush: Res<Brush>,
+    insert_mode: Res<InsertMode>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+    mut last_painted: Local<Option<(isize, isize)>>,
+) {
+    // Left clicks stamp patterns in insert mode; see `stamp_at_cursor`
+    let alive = if buttons.pressed(MouseButton::Left) && !insert_mode.0 {
+        true
+    } else if buttons.pressed(MouseButton::Right) {
+        false
+    } else {
+        *last_painted = None; // Stroke finished
+        return;
+    };
+
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some((grid_x, grid_y)) = cursor_cell(window, camera, camera_transform, &config) else {
+        return;
+    };
+
+    // Ensure the cursor is within the grid boundaries
+    if !on_grid(&grid, grid_x, grid_y) {
+        if buttons.any_just_pressed([MouseButton::Left, MouseButton::Right]) {
+            info!(
+                "Click outside grid bounds: ({}, {}), Grid size: {}x{}",
+                grid_x, grid_y, grid.width, grid.height
+            );
+        }
+        return;
+    }
+
+    // Holding still over a cell shouldn't repaint it every frame
+    if *last_painted == Some((grid_x, grid_y)) {
+        return;
+    }
+    // Snapshot once per stroke so a single undo reverts the whole drag
+    if last_painted.is_none() {
+        history.record(&grid);
+        info!("Painting from grid position: ({}, {})", grid_x, grid_y);
+    }
+    // Fast drags skip cells between frames, so fill in the line from the last one
+    let from = last_painted.unwrap_or((grid_x, grid_y));
+    for (x, y) in line_cells(from, (grid_x, grid_y)) {
+        paint(&mut grid, x, y, &brush, alive);
+    }
+    *last_painted = Some((grid_x, grid_y));
+}
+
+// Sets every cell under the brush centred on (x, y), clipped to a bounded grid or wrapped
+// round the edges of a toroidal one
+fn paint(grid: &mut Grid, x: isize, y: isize, brush: &Brush, alive: bool) {
+    let radius = brush.radius as isize;
+    for dx in -radius..=radius {
+        for dy in -radius..=radius {
+            if !brush.covers(dx, dy) {
+                continue;
+            }
+            if let Some((nx, ny)) = grid.wrap(x + dx, y + dy) {
+                grid.set(nx, ny, alive);
+            }
+        }
+    }
+}
+
+// Cells on the straight line between two cells, both ends included (Bresenham)
+fn line_cells(from: (isize, isize), to: (isize, isize)) -> Vec<(isize, isize)> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let (mut x, mut y) = from;
+    let mut error = dx + dy;
+    let mut cells = vec![from];
+    while (x, y) != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
+
+// HUD lines are screen-space UI text so they stay put while the camera zooms and pans
+fn hud_text(asset_server: &AssetServer, value: &str, top: f32) -> TextBundle {
+    TextBundle::from_section(
+        value.to_string(),
+        TextStyle {
+            font: asset_server.load("fonts/UniversCondensed.ttf"),
+            font_size: 20.0,
+            color: Color::WHITE,
+        },
+    )
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        top: Val::Px(top),
+        left: Val::Px(10.0),
+        ..Default::default()
+    })
+}
+
+fn setup_fps_counter(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((hud_text(&asset_server, "FPS: 0", 10.0), FpsText));
+}
+
+fn setup_population_counter(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        hud_text(&asset_server, "Population: 0", 35.0),
+        PopulationText,
+    ));
+}
+
+// Generic over the board so the infinite world shares the HUD counters
+fn update_population_counter<T: Life + Resource>(
+    board: Res<T>,
+    mut query: Query<&mut Text, With<PopulationText>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+    let count = board.count_live();
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Population: {}", count);
+    }
+}
+
+fn setup_generation_counter(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        hud_text(&asset_server, "Generation: 0", 60.0),
+        GenerationText,
+    ));
+}
+
+// The board only changes when it steps or is edited, so a paused board keeps its number
+fn update_generation_counter<T: Life + Resource>(
+    board: Res<T>,
+    mut query: Query<&mut Text, With<GenerationText>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Generation: {}", board.generation());
+    }
+}
+
+fn setup_tick_rate_counter(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+) {
+    let label = format!("Tick: {:.3}s", config.tick_rate);
+    commands.spawn((hud_text(&asset_server, &label, 85.0), TickRateText));
+}
+
+fn setup_status_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((hud_text(&asset_server, "", 135.0), StatusText));
+}
+
+fn setup_brush_text(mut commands: Commands, asset_server: Res<AssetServer>, brush: Res<Brush>) {
+    let label = brush.label();
+    commands.spawn((hud_text(&asset_server, &label, 110.0), BrushText));
+}
+
+// The seed stays the same for the whole run, as R draws from the same generator
+fn setup_seed_text(mut commands: Commands, asset_server: Res<AssetServer>, seed: Res<RngSeed>) {
+    let label = format!("Seed: {}", seed.0);
+    commands.spawn(hud_text(&asset_server, &label, 160.0));
+}
+
+// Ctrl+C puts the seed on the clipboard, for sharing a board to replay with --seed
+#[cfg(not(target_arch = "wasm32"))]
+fn copy_seed(keys: Res<Input<KeyCode>>, seed: Res<RngSeed>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::C) {
+        let copied = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(seed.0.to_string()));
+        match copied {
+            Ok(()) => info!("Seed {} copied to the clipboard", seed.0),
+            Err(err) => error!("Failed to copy the seed to the clipboard: {}", err),
+        }
+    }
+}
+
+// Number keys pick the brush radius, 0 painting a single cell, and `,` / `.` shrink or grow
+// it one step at a time up to MAX_BRUSH_RADIUS. Only the latter work in insert mode, where
+// the number keys pick patterns.
+fn adjust_brush(
+    keys: Res<Input<KeyCode>>,
+    mut brush: ResMut<Brush>,
+    insert_mode: Res<InsertMode>,
+    mut query: Query<&mut Text, With<BrushText>>,
+) {
+    const RADIUS_KEYS: [KeyCode; 10] = [
+        KeyCode::Key0,
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+        KeyCode::Key8,
+        KeyCode::Key9,
+    ];
+    let radius = if keys.just_pressed(KeyCode::Comma) {
+        brush.radius.saturating_sub(1)
+    } else if keys.just_pressed(KeyCode::Period) {
+        (brush.radius + 1).min(MAX_BRUSH_RADIUS)
+    } else if insert_mode.0 {
+        return;
+    } else {
+        match RADIUS_KEYS.iter().position(|&key| keys.just_pressed(key)) {
+            Some(radius) => radius,
+            None => return,
+        }
+    };
+    brush.radius = radius;
+    for mut text in query.iter_mut() {
+        text.sections[0].value = brush.label();
+    }
+    info!("Brush radius set to {}", radius);
+}
+
+fn toggle_insert_mode(
+    keys: Res<Input<KeyCode>>,
+    library: Res<PatternLibrary>,
+    selected: Res<SelectedPattern>,
+    mut insert_mode: ResMut<InsertMode>,
+) {
+    if keys.just_pressed(KeyCode::I) {
+        insert_mode.0 = !insert_mode.0;
+        if insert_mode.0 {
+            let (name, _) = &library.patterns[selected.0];
+            info!("Insert mode on, stamping {}", name);
+        } else {
+            info!("Insert mode off");
+        }
+    }
+}
+
+// In insert mode, number keys pick a pattern from the library
+fn select_pattern(
+    keys: Res<Input<KeyCode>>,
+    insert_mode: Res<InsertMode>,
+    library: Res<PatternLibrary>,
+    mut selected: ResMut<SelectedPattern>,
+) {
+    const PATTERN_KEYS: [KeyCode; 9] = [
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+        KeyCode::Key8,
+        KeyCode::Key9,
+    ];
+    if !insert_mode.0 {
+        return;
+    }
+    let pressed = PATTERN_KEYS.iter().position(|&key| keys.just_pressed(key));
+    if let Some(index) = pressed.filter(|&index| index < library.patterns.len()) {
+        selected.0 = index;
+        info!("Selected pattern: {}", library.patterns[index].0);
+    }
+}
+
+// In insert mode, a left click stamps the selected pattern centred on the cursor. Stamping
+// only adds live cells, so whatever is already on the board stays.
+#[allow(clippy::too_many_arguments)]
+fn stamp_at_cursor(
+    buttons: Res<Input<MouseButton>>,
+    insert_mode: Res<InsertMode>,
+    library: Res<PatternLibrary>,
+    selected: Res<SelectedPattern>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    if !insert_mode.0 || !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some((x, y)) = cursor_cell(window, camera, camera_transform, &config)
+        .filter(|&(x, y)| on_grid(&grid, x, y))
+    else {
+        return;
+    };
+    let (name, pattern) = &library.patterns[selected.0];
+    if pattern.width > grid.width || pattern.height > grid.height {
+        warn!(
+            "{} is {}x{}, too big for the {}x{} grid, so it will be clipped",
+            name, pattern.width, pattern.height, grid.width, grid.height
+        );
+    }
+    let (left, top) = stamp_origin(&grid, pattern, x, y);
+    history.record(&grid);
+    grid.insert_pattern(left, top, pattern);
+    info!("Stamped {} at ({}, {})", name, left, top);
+}
+
+// Top-left corner for centring `pattern` on (x, y), pulled inwards so it stays on the grid
+// (patterns larger than the grid are clipped by `insert_pattern`)
+fn stamp_origin(grid: &Grid, pattern: &Pattern, x: isize, y: isize) -> (usize, usize) {
+    let max_left = grid.width.saturating_sub(pattern.width) as isize;
+    let left = (x - pattern.width as isize / 2).clamp(0, max_left);
+    let min_top = pattern.height.min(grid.height) as isize - 1;
+    let top = (y + pattern.height as isize / 2).clamp(min_top, grid.height as isize - 1);
+    (left as usize, top as usize)
+}
+
+// O switches between square and round brushes
+fn toggle_brush_shape(
+    keys: Res<Input<KeyCode>>,
+    mut brush: ResMut<Brush>,
+    mut query: Query<&mut Text, With<BrushText>>,
+) {
+    if keys.just_pressed(KeyCode::O) {
+        brush.shape = match brush.shape {
+            BrushShape::Square => BrushShape::Circle,
+            BrushShape::Circle => BrushShape::Square,
+        };
+        for mut text in query.iter_mut() {
+            text.sections[0].value = brush.label();
+        }
+        info!("Brush shape set to {:?}", brush.shape);
+    }
+}
+
+// Both previews are unit-sized and scaled to the brush, so resizing needs no new meshes
+fn setup_brush_preview(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let color = Color::rgba(1.0, 1.0, 0.0, 0.25);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::ONE),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        BrushPreview(BrushShape::Square),
+    ));
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(shape::Circle::new(0.5).into()).into(),
+            material: materials.add(ColorMaterial::from(color)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        BrushPreview(BrushShape::Circle),
+    ));
+}
+
+// Keeps the preview of the current shape over the cell under the cursor, above the grid
+// lines, and hides it when the cursor is off the board
+fn update_brush_preview(
+    config: Res<GameConfig>,
+    grid: Res<Grid>,
+    brush: Res<Brush>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut previews: Query<(&BrushPreview, &mut Transform, &mut Visibility)>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let hovered = cursor_cell(window, camera, camera_transform, &config)
+        .filter(|&(x, y)| on_grid(&grid, x, y));
+    for (preview, mut transform, mut visibility) in previews.iter_mut() {
+        match hovered {
+            Some((x, y)) if preview.0 == brush.shape => {
+                let centre = cell_position(&config, x as usize, y as usize);
+                let diameter = (2 * brush.radius + 1) as f32 * config.cell_size;
+                transform.translation = centre.truncate().extend(2.0);
+                transform.scale = Vec3::new(diameter, diameter, 1.0);
+                *visibility = Visibility::Inherited;
+            }
+            _ => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+// Announces when the board settles. An empty board is stable too, but reads better as
+// extinct. `settled` remembers where it settled while the generations keep counting.
+fn update_status_text(
+    config: Res<GameConfig>,
+    grid: Res<Grid>,
+    mut settled: Local<Option<u64>>,
+    mut query: Query<&mut Text, With<StatusText>>,
+) {
+    if !grid.is_changed() {
+        return;
+    }
+    match grid.period {
+        None => *settled = None,
+        // The repeat is noticed a full period after the board first entered the cycle
+        Some(period) if settled.is_none() => {
+            *settled = Some(grid.generation.saturating_sub(period as u64));
+        }
+        Some(_) => {}
+    }
+    let status = match (*settled, grid.period) {
+        _ if config.reached_max_generations(&grid) => format!("Reached gen {}", grid.generation),
+        (Some(generation), Some(1)) if grid.count_live() == 0 => {
+            format!("Extinct at gen {}", generation)
+        }
+        (Some(generation), Some(1)) => format!("Stable at gen {}", generation),
+        (Some(generation), Some(period)) => {
+            format!("Period {} since gen {}", period, generation)
+        }
+        _ => String::new(),
+    };
+    for mut text in query.iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+// With --auto-pause, stops the clock when the board repeats one from the last
+// --cycle-window generations. Only the moment it settles counts, so resuming with Space
+// keeps it running.
+fn auto_pause(
+    config: Res<GameConfig>,
+    grid: Res<Grid>,
+    mut state: ResMut<SimState>,
+    mut was_settled: Local<bool>,
+) {
+    let settled = grid.period.is_some();
+    if config.auto_pause_on_cycle && settled && !*was_settled && *state == SimState::Running {
+        *state = SimState::Paused;
+        let period = grid.period.unwrap_or(1);
+        info!(
+            "Stabilized (period {}) at generation {}, pausing",
+            period, grid.generation
+        );
+    }
+    *was_settled = settled;
+}
+
+// With --max-gens, pauses once the board reaches the limit. Runs before `update_grid`, which
+// also refuses to step past it, so resuming with Space stays put until the board is reset.
+fn stop_at_max_generations(config: Res<GameConfig>, grid: Res<Grid>, mut state: ResMut<SimState>) {
+    if config.reached_max_generations(&grid) && *state == SimState::Running {
+        *state = SimState::Paused;
+        info!("Reached generation {}, pausing", grid.generation);
+    }
+}
+
+// Puts the replay's current frame on the grid, with the frame before it as the previous
+// generation so cells are coloured by how they changed
+fn show_frame(grid: &mut Grid, replay: &Replay) {
+    let frames = &replay.recording.frames;
+    if frames[replay.frame].len() != grid.cells.len() {
+        error!(
+            "Replay frame {} doesn't fit the {}x{} grid",
+            replay.frame, grid.width, grid.height
+        );
+        return;
+    }
+    grid.prev_cells
+        .copy_from_slice(&frames[replay.frame.saturating_sub(1)]);
+    grid.cells.copy_from_slice(&frames[replay.frame]);
+    grid.age.fill(0);
+    grid.dying.fill(0);
+    grid.generation = replay.frame as u64;
+    grid.mark_edited();
+}
+
+fn start_replay(mut grid: ResMut<Grid>, replay: Res<Replay>) {
+    show_frame(&mut grid, &replay);
+    info!("Replaying {} frames", replay.recording.frames.len());
+}
+
+// Plays on at the tick rate and pauses on the last frame
+fn play_replay(
+    time: Res<Time>,
+    mut timer: ResMut<TickTimer>,
+    mut state: ResMut<SimState>,
+    mut replay: ResMut<Replay>,
+    mut grid: ResMut<Grid>,
+) {
+    if *state != SimState::Running || !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    if replay.frame + 1 < replay.recording.frames.len() {
+        replay.frame += 1;
+        show_frame(&mut grid, &replay);
+    } else {
+        *state = SimState::Paused;
+        info!("Replay finished at frame {}", replay.frame);
+    }
+}
+
+// Right and Left step through the frames while paused, as they step the live grid
+fn scrub_replay(
+    keys: Res<Input<KeyCode>>,
+    state: Res<SimState>,
+    mut replay: ResMut<Replay>,
+    mut grid: ResMut<Grid>,
+) {
+    if *state != SimState::Paused || keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let last = replay.recording.frames.len() - 1;
+    let frame = if keys.just_pressed(KeyCode::Right) {
+        (replay.frame + 1).min(last)
+    } else if keys.just_pressed(KeyCode::Left) {
+        replay.frame.saturating_sub(1)
+    } else {
+        return;
+    };
+    replay.frame = frame;
+    show_frame(&mut grid, &replay);
+}
+
+// Runs after `setup` has made the starting board, which becomes the first frame
+#[cfg(not(target_arch = "wasm32"))]
+fn start_recording(mut commands: Commands, path: Res<RecordPath>, grid: Res<Grid>) {
+    let created = fs::File::create(&path.0).and_then(|mut file| {
+        file.write_all(&recording::encode_header(grid.width, grid.height))?;
+        Ok(file)
+    });
+    match created {
+        Ok(file) => {
+            info!("Recording to {}", path.0);
+            commands.insert_resource(Recorder {
+                file,
+                size: (grid.width, grid.height),
+                last_generation: None,
+            });
+        }
+        Err(err) => error!("Failed to start recording to {}: {}", path.0, err),
+    }
+}
+
+// Appends a frame whenever the generation changes, including by rewinding or resetting.
+// Each frame goes out in one write, so a run cut short still ends on a whole frame.
+#[cfg(not(target_arch = "wasm32"))]
+fn record_generations(mut commands: Commands, grid: Res<Grid>, recorder: Option<ResMut<Recorder>>) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    if recorder.last_generation == Some(grid.generation) {
+        return;
+    }
+    if recorder.size != (grid.width, grid.height) {
+        warn!("Recording stopped: the grid was resized");
+        commands.remove_resource::<Recorder>();
+        return;
+    }
+    let frame = recording::encode_frame(&grid.cells);
+    match recorder.file.write_all(&frame) {
+        Ok(()) => recorder.last_generation = Some(grid.generation),
+        Err(err) => {
+            error!("Recording stopped: {}", err);
+            commands.remove_resource::<Recorder>();
+        }
+    }
+}
+
+// Starts hidden; M shows it
+fn setup_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: 1,
+        height: 1,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest(); // Keep cells crisp when scaled up
+    let minimap = ImageBundle {
+        image: UiImage::new(images.add(image)),
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            ..default()
+        },
+        visibility: Visibility::Hidden,
+        ..default()
+    };
+    let viewport = NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        border_color: BorderColor(Color::YELLOW),
+        ..default()
+    };
+    commands.spawn((minimap, Minimap)).with_children(|parent| {
+        parent.spawn((viewport, MinimapViewport));
+    });
+}
+
+fn toggle_minimap(keys: Res<Input<KeyCode>>, mut query: Query<&mut Visibility, With<Minimap>>) {
+    if keys.just_pressed(KeyCode::M) {
+        for mut visibility in query.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+// Redraws the whole grid into the minimap every MINIMAP_REFRESH seconds rather than every
+// frame, and straight away when it is shown. Large grids are scaled down so each pixel
+// covers a square block of cells, shown in the colour of its first live cell so that small
+// patterns don't vanish.
+fn update_minimap(
+    time: Res<Time>,
+    mut timer: ResMut<MinimapTimer>,
+    grid: Res<Grid>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    mut images: ResMut<Assets<Image>>,
+    mut query: Query<(&UiImage, &mut Style, Ref<Visibility>), With<Minimap>>,
+) {
+    let Ok((handle, mut style, visibility)) = query.get_single_mut() else {
+        return;
+    };
+    let due = timer.0.tick(time.delta()).just_finished();
+    if *visibility == Visibility::Hidden || !(due || visibility.is_changed()) {
+        return;
+    }
+    let Some(image) = images.get_mut(&handle.texture) else {
+        return;
+    };
+
+    let block = grid.width.max(grid.height).div_ceil(MINIMAP_MAX_PIXELS);
+    let (width, height) = (grid.width.div_ceil(block), grid.height.div_ceil(block));
+    let size = Extent3d {
+        width: width as u32,
+        height: height as u32,
+        depth_or_array_layers: 1,
+    };
+    if image.texture_descriptor.size != size {
+        image.resize(size);
+        let longest = width.max(height) as f32;
+        style.width = Val::Px(MINIMAP_SIZE * width as f32 / longest);
+        style.height = Val::Px(MINIMAP_SIZE * height as f32 / longest);
+    }
+    for row in 0..height {
+        // Image rows run downwards, grid y upwards
+        let ys = grid.height.saturating_sub((row + 1) * block)..grid.height - row * block;
+        for col in 0..width {
+            let xs = col * block..((col + 1) * block).min(grid.width);
+            let live = xs
+                .flat_map(|x| ys.clone().map(move |y| (x, y)))
+                .find(|&(x, y)| grid.get(x, y));
+            let color = match live {
+                Some((x, y)) => display_color(*mode, &theme, &grid, x, y),
+                None => theme.dead,
+            };
+            let i = (row * width + col) * 4;
+            image.data[i..i + 4].copy_from_slice(&color.as_rgba_u8());
+        }
+    }
+}
+
+// Outlines the part of the grid the camera shows; cheap, so it follows every frame
+fn update_minimap_viewport(
+    grid: Res<Grid>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut query: Query<&mut Style, With<MinimapViewport>>,
+) {
+    let (Ok(window), Ok((transform, projection))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let (xs, ys) = visible_cells(window, transform, projection, &config);
+    // Fraction of the grid's width or height, with the view clamped to the grid
+    let fraction = |cell: isize, cells: usize| cell.clamp(0, cells as isize) as f32 / cells as f32;
+    for mut style in query.iter_mut() {
+        let left = fraction(*xs.start(), grid.width);
+        let bottom = fraction(*ys.start(), grid.height);
+        style.left = Val::Percent(100.0 * left);
+        style.bottom = Val::Percent(100.0 * bottom);
+        style.width = Val::Percent(100.0 * (fraction(*xs.end() + 1, grid.width) - left));
+        style.height = Val::Percent(100.0 * (fraction(*ys.end() + 1, grid.height) - bottom));
+    }
+}
+
+fn update_fps_counter(
+    diagnostics: Res<DiagnosticsStore>,
+    mut query: Query<&mut Text, With<FpsText>>,
+) {
+    if let Some(fps) = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.average())
+    {
+        for mut text in query.iter_mut() {
+            text.sections[0].value = format!("FPS: {:.0}", fps);
+        }
+    }
+}
+
+fn toggle_pause(keys: Res<Input<KeyCode>>, mut state: ResMut<SimState>) {
+    if keys.just_pressed(KeyCode::Space) {
+        *state = match *state {
+            SimState::Running => SimState::Paused,
+            SimState::Paused => SimState::Running,
+        };
+        info!("Simulation {:?}", *state);
+    }
+}
+
+// Swaps the grid's rule for Brian's Brain, and back to the rule it replaced
+fn toggle_brians_brain(
+    keys: Res<Input<KeyCode>>,
+    mut grid: ResMut<Grid>,
+    mut replaced: Local<Option<Ruleset>>,
+) {
+    if keys.just_pressed(KeyCode::B) {
+        grid.ruleset = if grid.ruleset == Ruleset::brians_brain() {
+            replaced.take().unwrap_or_default()
+        } else {
+            replaced.replace(grid.ruleset);
+            Ruleset::brians_brain()
+        };
+        grid.mark_edited();
+        info!("Rule: {}", grid.ruleset);
+    }
+}
+
+fn toggle_topology(keys: Res<Input<KeyCode>>, mut grid: ResMut<Grid>) {
+    if keys.just_pressed(KeyCode::Tab) {
+        grid.topology = match grid.topology {
+            Topology::Bounded => Topology::Toroidal,
+            Topology::Toroidal => Topology::Bounded,
+        };
+        info!("Grid topology set to {:?}", grid.topology);
+    }
+}
+
+// Flips between the Moore and Von Neumann neighbourhoods; a wider Moore range from
+// --neighborhood switches to Von Neumann too
+fn toggle_neighborhood(keys: Res<Input<KeyCode>>, mut grid: ResMut<Grid>) {
+    if keys.just_pressed(KeyCode::N) {
+        grid.neighborhood = match grid.neighborhood {
+            Neighborhood::VonNeumann => Neighborhood::Moore,
+            Neighborhood::Moore | Neighborhood::MooreRange(_) => Neighborhood::VonNeumann,
+        };
+        grid.mark_edited();
+        info!("Neighbourhood set to {:?}", grid.neighborhood);
+    }
+}
+
+// Plain C only; Ctrl+C copies the seed
+fn clear_grid(keys: Res<Input<KeyCode>>, mut grid: ResMut<Grid>, mut history: ResMut<History>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl && keys.just_pressed(KeyCode::C) {
+        history.record(&grid);
+        grid.clear();
+        info!("Grid cleared");
+    }
+}
+
+fn randomize_grid(
+    keys: Res<Input<KeyCode>>,
+    random: Res<RandomConfig>,
+    mut rng: ResMut<RngResource>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    if keys.just_pressed(KeyCode::R) {
+        history.record(&grid);
+        grid.randomize(random.density, &mut rng.0);
+        info!("Grid randomized with density {}", random.density);
+    }
+}
+
+// Numpad +/- nudge the density used by the next randomize
+fn adjust_density(keys: Res<Input<KeyCode>>, mut random: ResMut<RandomConfig>) {
+    let delta = if keys.just_pressed(KeyCode::NumpadAdd) {
+        0.05
+    } else if keys.just_pressed(KeyCode::NumpadSubtract) {
+        -0.05
+    } else {
+        return;
+    };
+    random.density = (random.density + delta).clamp(0.05, 0.95);
+    info!("Random density set to {:.2}", random.density);
+}
+
+// Ctrl+S, since plain S pans the camera
+#[cfg(not(target_arch = "wasm32"))]
+fn save_grid(keys: Res<Input<KeyCode>>, grid: Res<Grid>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::S) {
+        match grid.save_to_path(SAVE_PATH) {
+            Ok(()) => info!("Grid saved to {}", SAVE_PATH),
+            Err(err) => error!("Failed to save grid to {}: {}", SAVE_PATH, err),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_grid(
+    keys: Res<Input<KeyCode>>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+    mut resized: EventWriter<GridResized>,
+) {
+    if keys.just_pressed(KeyCode::L) {
+        match Grid::load_from_path(SAVE_PATH) {
+            // Resizing has the same limit, and the board needs a sprite per cell
+            Ok(loaded) if loaded.width > MAX_GRID_SIZE || loaded.height > MAX_GRID_SIZE => {
+                error!(
+                    "{} is {}x{}, larger than the {} cell limit",
+                    SAVE_PATH, loaded.width, loaded.height, MAX_GRID_SIZE
+                );
+            }
+            Ok(mut loaded) => {
+                history.record(&grid);
+                // Keep the current settings; only the board comes from the file
+                loaded.topology = grid.topology;
+                loaded.neighborhood = grid.neighborhood;
+                loaded.ruleset = grid.ruleset;
+                loaded.cycle_window = grid.cycle_window;
+                if (loaded.width, loaded.height) != (grid.width, grid.height) {
+                    resized.send(GridResized);
+                }
+                *grid = loaded;
+                info!("Grid loaded from {}", SAVE_PATH);
+            }
+            Err(err) => error!("Failed to load grid from {}: {}", SAVE_PATH, err),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn export_rle(keys: Res<Input<KeyCode>>, grid: Res<Grid>) {
+    if keys.just_pressed(KeyCode::E) {
+        let rle = grid.to_rle();
+        match fs::write(EXPORT_PATH, &rle) {
+            Ok(()) => info!("Grid exported to {}:\n{}", EXPORT_PATH, rle),
+            Err(err) => error!("Failed to export grid to {}: {}", EXPORT_PATH, err),
+        }
+    }
+}
+
+// Renders the cells straight into an image, one PNG_CELL_PIXELS square per cell, so the
+// export has the full grid at a fixed resolution whatever the zoom or window size. P names
+// the file after the generation, F12 after the time, so repeated screenshots don't collide.
+#[cfg(not(target_arch = "wasm32"))]
+fn export_png(keys: Res<Input<KeyCode>>, grid: Res<Grid>, mode: Res<ColorMode>, theme: Res<Theme>) {
+    let path = if keys.just_pressed(KeyCode::F12) {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        format!("screenshot_{}.png", now.unwrap_or_default().as_millis())
+    } else if keys.just_pressed(KeyCode::P) {
+        format!("generation_{}.png", grid.generation)
+    } else {
+        return;
+    };
+    let image = grid.render_to_image(PNG_CELL_PIXELS, |x, y| {
+        display_color(*mode, &theme, &grid, x, y).as_rgba_u8()
+    });
+    match image.save(&path) {
+        Ok(()) => info!("Grid exported to {}", path),
+        Err(err) => error!("Failed to export grid to {}: {}", path, err),
+    }
+}
+
+// V starts capturing a frame per generation, and pressing it again writes the frames out as
+// an animated GIF playing at the current tick rate. Capture also stops on its own once the
+// frames take MAX_GIF_BYTES, so a forgotten recording can't grow without bound, and when
+// the grid is resized, since a GIF's frames all have one size.
+#[cfg(not(target_arch = "wasm32"))]
+fn record_gif(
+    keys: Res<Input<KeyCode>>,
+    grid: Res<Grid>,
+    mode: Res<ColorMode>,
+    theme: Res<Theme>,
+    timer: Res<TickTimer>,
+    mut capture: ResMut<GifCapture>,
+) {
+    let capture = &mut *capture;
+    let toggled = keys.just_pressed(KeyCode::V);
+    let Some(frames) = &mut capture.frames else {
+        if toggled {
+            capture.frames = Some(Vec::new());
+            capture.size = (grid.width, grid.height);
+            capture.last_generation = None;
+            info!("Recording a GIF, press V again to stop");
+        }
+        return;
+    };
+    let resized = capture.size != (grid.width, grid.height);
+    if !toggled && !resized && capture.last_generation != Some(grid.generation) {
+        frames.push(grid.render_to_image(GIF_CELL_PIXELS, |x, y| {
+            display_color(*mode, &theme, &grid, x, y).as_rgba_u8()
+        }));
+        capture.last_generation = Some(grid.generation);
+    }
+    let bytes: usize = frames.iter().map(|frame| frame.as_raw().len()).sum();
+    let full = bytes >= MAX_GIF_BYTES;
+    if !toggled && !full && !resized {
+        return;
+    }
+    if resized {
+        warn!("GIF recording stopped: the grid was resized");
+    } else if full {
+        info!(
+            "GIF recording reached {} frames, the memory limit",
+            frames.len()
+        );
+    }
+    let frames = std::mem::take(frames);
+    capture.frames = None;
+    let path = format!("generation_{}.gif", grid.generation);
+    let delay = timer.0.duration();
+    // Quantizing every frame takes a while, so it runs off the main thread
+    std::thread::spawn(move || match write_gif(&path, &frames, delay) {
+        Ok(()) => info!("Wrote {} frames to {}", frames.len(), path),
+        Err(err) => error!("Failed to write {}: {}", path, err),
+    });
+}
+
+// Browsers wait at least 2 centiseconds between frames, so faster ticks are slowed to that
+#[cfg(not(target_arch = "wasm32"))]
+fn write_gif(path: &str, frames: &[image::RgbaImage], delay: Duration) -> Result<(), String> {
+    let Some(first) = frames.first() else {
+        return Err("no generations were captured".to_string());
+    };
+    let (Ok(width), Ok(height)) = (u16::try_from(first.width()), u16::try_from(first.height()))
+    else {
+        let (width, height) = first.dimensions();
+        return Err(format!(
+            "{}x{} pixels is too large for a GIF",
+            width, height
+        ));
+    };
+    if frames
+        .iter()
+        .any(|image| image.dimensions() != first.dimensions())
+    {
+        return Err("the frames aren't all the same size".to_string());
+    }
+    let file = fs::File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = gif::Encoder::new(file, width, height, &[]).map_err(|err| err.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|err| err.to_string())?;
+    let centiseconds = (delay.as_millis() / 10).clamp(2, u16::MAX as u128) as u16;
+    for image in frames {
+        let mut pixels = image.as_raw().clone();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 30);
+        frame.delay = centiseconds;
+        encoder.write_frame(&frame).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+// Ctrl+Z undoes the last step or edit, Ctrl+Y or Ctrl+Shift+Z redoes it
+fn undo_redo(keys: Res<Input<KeyCode>>, mut grid: ResMut<Grid>, mut history: ResMut<History>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let redo = keys.just_pressed(KeyCode::Y) || (shift && keys.just_pressed(KeyCode::Z));
+    if redo {
+        if history.redo(&mut grid) {
+            info!("Redo ({} more available)", history.redo_count());
+        }
+    } else if keys.just_pressed(KeyCode::Z) && history.undo(&mut grid) {
+        info!("Undo ({} more available)", history.undo_count());
+    }
+}
+
+fn step_once(
+    keys: Res<Input<KeyCode>>,
+    state: Res<SimState>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    // Stepping while running would double up with the tick timer, and Shift+Right nudges
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if *state == SimState::Paused && !shift && keys.just_pressed(KeyCode::Right) {
+        history.record(&grid);
+        grid.step();
+        info!("Advanced one generation");
+    }
+}
+
+// Left steps back a generation while paused, the mirror of `step_once`. It is undo under
+// another name, so an edit made since the last step is reverted first.
+fn rewind(
+    keys: Res<Input<KeyCode>>,
+    state: Res<SimState>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if *state == SimState::Paused && !shift && keys.just_pressed(KeyCode::Left) {
+        if history.undo(&mut grid) {
+            info!("Rewound to generation {}", grid.generation);
+        } else {
+            info!("Nothing left to rewind");
+        }
+    }
+}
+
+// Scroll to zoom, drag with the middle button to pan. Zoom changes the projection scale
+// rather than the transform, on top of the fit-to-window scale set in `setup`; the click
+// mapping in `handle_clicks` goes through `viewport_to_world_2d`, so it follows both.
+fn camera_control(
+    buttons: Res<Input<MouseButton>>,
+    mut scroll: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let notches: f32 = scroll
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        })
+        .sum();
+    let drag: Vec2 = motion.iter().map(|event| event.delta).sum();
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    if notches != 0.0 {
+        projection.scale = (projection.scale * 0.9_f32.powf(notches)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+    if buttons.pressed(MouseButton::Middle) && drag != Vec2::ZERO {
+        // Motion is in screen pixels, y down; convert through the camera's world-per-pixel scale
+        transform.translation.x -= drag.x * projection.scale * transform.scale.x;
+        transform.translation.y += drag.y * projection.scale * transform.scale.y;
+    }
+}
+
+// `=` or `]` halves the time between updates, `-` or `[` doubles it
+fn adjust_speed(
+    keys: Res<Input<KeyCode>>,
+    mut timer: ResMut<TickTimer>,
+    mut query: Query<&mut Text, With<TickRateText>>,
+) {
+    // Ctrl+brackets resize the grid instead
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let factor = if keys.any_just_pressed([KeyCode::Equals, KeyCode::BracketRight]) {
+        0.5
+    } else if keys.any_just_pressed([KeyCode::Minus, KeyCode::BracketLeft]) {
+        2.0
+    } else {
+        return;
+    };
+    let duration = timer.0.duration().as_secs_f32();
+    let tick_rate = (duration * factor).clamp(MIN_TICK_RATE, MAX_TICK_RATE);
+    // Carry the elapsed fraction over so the next step doesn't fire early or late
+    let elapsed = tick_rate * timer.0.elapsed_secs() / duration;
+    timer.0.set_duration(Duration::from_secs_f32(tick_rate));
+    timer.0.set_elapsed(Duration::from_secs_f32(elapsed));
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Tick: {:.3}s", tick_rate);
+    }
+    info!("Tick rate set to {:.3}s", tick_rate);
+}
+
+// WASD (plus the up/down arrows) pan the camera; left/right rewind and step
+fn keyboard_pan(
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    // Ctrl is reserved for shortcuts such as Ctrl+S
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    // Shift+arrows nudge the board instead
+    let arrows = !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut direction = Vec2::ZERO;
+    if keys.pressed(KeyCode::W) || (arrows && keys.pressed(KeyCode::Up)) {
+        direction.y += 1.0;
+    }
+    if keys.pressed(KeyCode::S) || (arrows && keys.pressed(KeyCode::Down)) {
+        direction.y -= 1.0;
+    }
+    if keys.pressed(KeyCode::A) {
+        direction.x -= 1.0;
+    }
+    if keys.pressed(KeyCode::D) {
+        direction.x += 1.0;
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+    for (mut transform, projection) in cameras.iter_mut() {
+        // Scale by zoom so the view moves at the same on-screen speed however far in we are
+        let step = direction.normalize() * PAN_SPEED * projection.scale * time.delta_seconds();
+        transform.translation += step.extend(0.0);
+    }
+}
+
+// Shift+arrows move everything on the board a cell at a time while paused, e.g. to centre
+// a pattern. Each press is its own undo step.
+fn nudge_grid(
+    keys: Res<Input<KeyCode>>,
+    state: Res<SimState>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    if *state != SimState::Paused || !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let (dx, dy) = if keys.just_pressed(KeyCode::Left) {
+        (-1, 0)
+    } else if keys.just_pressed(KeyCode::Right) {
+        (1, 0)
+    } else if keys.just_pressed(KeyCode::Up) {
+        (0, 1)
+    } else if keys.just_pressed(KeyCode::Down) {
+        (0, -1)
+    } else {
+        return;
+    };
+    history.record(&grid);
+    grid.shift_grid(dx, dy);
+}
+
+// X and Y mirror the board, Q turns it a quarter turn clockwise
+fn transform_grid(keys: Res<Input<KeyCode>>, mut grid: ResMut<Grid>, mut history: ResMut<History>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if keys.just_pressed(KeyCode::X) {
+        history.record(&grid);
+        grid.flip_h();
+    } else if !ctrl && keys.just_pressed(KeyCode::Y) {
+        history.record(&grid);
+        grid.flip_v();
+    } else if keys.just_pressed(KeyCode::Q) {
+        // Only a turn that happens goes into the history
+        if grid.width == grid.height {
+            history.record(&grid);
+        }
+        if let Err(error) = grid.rotate_cw() {
+            warn!("{}", error);
+        }
+    }
+}
+
+fn update_grid(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    random: Res<RandomConfig>,
+    mut timer: ResMut<TickTimer>,
+    mut rng: ResMut<RngResource>,
+    mut grid: ResMut<Grid>,
+    mut history: ResMut<History>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() || config.reached_max_generations(&grid) {
+        return;
+    }
+    history.record(&grid);
+    advance(&mut grid);
+
+    // Only the step that kills the last cell counts, not every step of an empty board
+    if grid.count_live() == 0 && grid.prev_cells.contains(&true) {
+        info!("Population extinct at generation {}", grid.generation);
+        // With --restart-on-empty a demo keeps going on its own
+        if config.restart_on_empty {
+            history.record(&grid);
+            grid.randomize(random.density, &mut rng.0);
+            info!("Grid re-randomized with density {}", random.density);
+        }
+    }
+}
+
+// Large grids step on every core when built with `--features parallel`
+fn advance(grid: &mut Grid) {
+    #[cfg(feature = "parallel")]
+    grid.step_parallel();
+    #[cfg(not(feature = "parallel"))]
+    grid.step();
+}
+
+// Generations to run for `--headless N`
+#[derive(Resource)]
+struct HeadlessRun(u64);
+
+// Runs the whole headless session in one go: stepping back to back, without the tick
+// timer, times just the simulation. MinimalPlugins has no logger, so this prints.
+fn run_headless(mut grid: ResMut<Grid>, run: Res<HeadlessRun>, mut exit: EventWriter<AppExit>) {
+    let start = Instant::now();
+    for _ in 0..run.0 {
+        advance(&mut grid);
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{} generations of a {}x{} grid in {:?} ({:.0} generations/s), final population {}",
+        run.0,
+        grid.width,
+        grid.height,
+        elapsed,
+        run.0 as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        grid.count_live()
+    );
+    exit.send(AppExit);
+}
+
+// Live cell sprites for the infinite world, reused from frame to frame
+#[derive(Component)]
+struct WorldCell;
+
+// The infinite world has no edges to draw and no per-cell sprites. The grid size only sets
+// where the starting board goes and how much of it the camera first shows.
+fn setup_infinite(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    world: Res<SparseGrid>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let live = world.count_live();
+    info!("Setting up an infinite world with {} live cells", live);
+    spawn_camera(&mut commands, &config, windows.get_single().ok());
+    commands.spawn((hud_text(&asset_server, "", 135.0), BoundsText));
+}
+
+fn update_world(time: Res<Time>, mut timer: ResMut<TickTimer>, mut world: ResMut<SparseGrid>) {
+    if timer.0.tick(time.delta()).just_finished() {
+        world.step();
+    }
+}
+
+// Like `handle_clicks`, but nothing is off the board and there's no undo history
+fn paint_world(
+    buttons: Res<Input<MouseButton>>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    brush: Res<Brush>,
+    mut world: ResMut<SparseGrid>,
+    mut last_painted: Local<Option<(isize, isize)>>,
+) {
+    let alive = if buttons.pressed(MouseButton::Left) {
+        true
+    } else if buttons.pressed(MouseButton::Right) {
+        false
+    } else {
+        *last_painted = None;
+        return;
+    };
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some(cell) = cursor_cell(window, camera, camera_transform, &config) else {
+        return;
+    };
+    if *last_painted == Some(cell) {
+        return;
+    }
+
+    let radius = brush.radius as isize;
+    for (x, y) in line_cells(last_painted.unwrap_or(cell), cell) {
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                if brush.covers(dx, dy) {
+                    world.set((x + dx) as i64, (y + dy) as i64, alive);
+                }
+            }
+        }
+    }
+    *last_painted = Some(cell);
+}
+
+// Draws the live cells inside the camera's view, however far it has moved. Sprites are
+// pooled: extra ones are hidden rather than despawned, and the pool grows when the view
+// holds more cells than ever before.
+#[allow(clippy::too_many_arguments)]
+fn render_world(
+    mut commands: Commands,
+    world: Res<SparseGrid>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Transform, &OrthographicProjection), (With<MainCamera>, Without<WorldCell>)>,
+    mut sprites: Query<(&mut Transform, &mut Visibility, &mut Sprite), With<WorldCell>>,
+) {
+    let (Ok(window), Ok((camera_transform, projection))) =
+        (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let (xs, ys) = visible_cells(window, camera_transform, projection, &config);
+    let mut visible = world
+        .live
+        .iter()
+        .filter(|&&(x, y)| xs.contains(&(x as isize)) && ys.contains(&(y as isize)));
+
+    if theme.is_changed() {
+        clear_color.0 = theme.dead;
+    }
+    for (mut transform, mut visibility, mut sprite) in sprites.iter_mut() {
+        match visible.next() {
+            Some(&(x, y)) => {
+                transform.translation = world_cell_position(&config, x, y);
+                *visibility = Visibility::Inherited;
+                sprite.color = theme.alive;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for &(x, y) in visible {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: theme.alive,
+                    custom_size: Some(Vec2::splat(config.cell_size)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(world_cell_position(&config, x, y)),
+                ..Default::default()
+            },
+            WorldCell,
+        ));
+    }
+}
+
+fn update_bounds_text(world: Res<SparseGrid>, mut query: Query<&mut Text, With<BoundsText>>) {
+    if !world.is_changed() {
+        return;
+    }
+    let bounds = match world.bounds() {
+        Some((min_x, min_y, max_x, max_y)) => format!(
+            "Bounds: ({}, {}) to ({}, {}), {}x{}",
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            max_x - min_x + 1,
+            max_y - min_y + 1
+        ),
+        None => "Bounds: empty".to_string(),
+    };
+    for mut text in query.iter_mut() {
+        text.sections[0].value = bounds.clone();
+    }
+}
+
+// `--infinite`: gliders fly off forever instead of dying at the edge or wrapping. Only the
+// systems that make sense without a fixed grid are added; the grid's editing, file and
+// colouring features all assume its bounds.
+fn run_infinite(config: GameConfig, theme: Theme, world: SparseGrid) {
+    App::new()
+        .add_plugins(DefaultPlugins.set(window_plugin()))
+        .add_plugins(FrameTimeDiagnosticsPlugin)
+        .insert_resource(world)
+        .insert_resource(TickTimer(Timer::from_seconds(
+            config.tick_rate,
+            TimerMode::Repeating,
+        )))
+        .insert_resource(config)
+        .insert_resource(SimState::Running)
+        .init_resource::<Brush>()
+        .init_resource::<InsertMode>()
+        .insert_resource(theme)
+        .add_systems(Startup, setup_infinite)
+        .add_systems(Startup, setup_fps_counter)
+        .add_systems(Startup, setup_population_counter)
+        .add_systems(Startup, setup_generation_counter)
+        .add_systems(Startup, setup_tick_rate_counter)
+        .add_systems(Startup, setup_brush_text)
+        .add_systems(Update, toggle_pause)
+        .add_systems(
+            Update,
+            update_world.run_if(resource_equals(SimState::Running)),
+        )
+        .add_systems(Update, paint_world)
+        .add_systems(
+            Update,
+            render_world.after(camera_control).after(keyboard_pan),
+        )
+        .add_systems(Update, camera_control)
+        .add_systems(Update, keyboard_pan)
+        .add_systems(Update, cycle_theme)
+        .add_systems(Update, adjust_brush)
+        .add_systems(Update, toggle_brush_shape)
+        .add_systems(Update, adjust_speed)
+        .add_systems(Update, update_fps_counter)
+        .add_systems(Update, update_population_counter::<SparseGrid>)
+        .add_systems(Update, update_generation_counter::<SparseGrid>)
+        .add_systems(Update, update_bounds_text)
+        .run();
+}
+
+// The main window; in the browser it draws into the page's canvas instead
+pub fn window_plugin() -> WindowPlugin {
+    WindowPlugin {
+        primary_window: Some(Window {
+            title: "Rusty Game of Life".to_string(),
+            resolution: (1000.0, 720.0).into(), // Set the initial window size
+            // On the web, draw into the page's <canvas id="bevy"> and follow its size
+            #[cfg(target_arch = "wasm32")]
+            canvas: Some("#bevy".to_string()),
+            #[cfg(target_arch = "wasm32")]
+            fit_canvas_to_parent: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// The whole windowed game as one plugin, added to an app that has the default plugins.
+/// Settings start from the constants above; set them through the `with_` methods or the
+/// fields. The binary fills them in from config.toml and the command line.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use rusty_game_of_life_with_bevy::plugin::{window_plugin, GameOfLifePlugin};
+/// use rusty_game_of_life_with_bevy::Ruleset;
+///
+/// let game = GameOfLifePlugin::default()
+///     .with_size(80, 60)
+///     .with_tick_rate(0.05)
+///     .with_ruleset(Ruleset::parse("B36/S23").unwrap())
+///     .with_seed(42);
+/// App::new()
+///     .add_plugins(DefaultPlugins.set(window_plugin()))
+///     .add_plugins(game)
+///     .run();
+/// ```
+pub struct GameOfLifePlugin {
+    pub config: GameConfig,
+    pub ruleset: Ruleset,
+    pub density: f64, // Chance for each cell to start alive
+    pub theme: Theme,
+    pub neighborhood: Neighborhood,
+    pub cycle_window: usize,
+    pub seed: u64,
+    pub start: Option<Pattern>, // Stamped at startup in place of the random board
+    pub replay: Option<Recording>, // Played back instead of stepping the grid
+    pub record: Option<String>, // File each generation is appended to
+    pub restore_autosave: bool, // Start from the last session's board when it fits
+}
+
+impl Default for GameOfLifePlugin {
+    fn default() -> Self {
+        Self {
+            config: GameConfig::default(),
+            ruleset: Ruleset::default(),
+            density: RandomConfig::default().density,
+            theme: Theme::default(),
+            neighborhood: Neighborhood::default(),
+            cycle_window: CYCLE_WINDOW,
+            seed: rand::random(),
+            start: None,
+            replay: None,
+            record: None,
+            restore_autosave: true,
+        }
+    }
+}
+
+impl GameOfLifePlugin {
+    pub fn with_size(mut self, width: usize, height: usize) -> Self {
+        self.config.grid_width = width;
+        self.config.grid_height = height;
+        self
+    }
+
+    pub fn with_tick_rate(mut self, tick_rate: f32) -> Self {
+        self.config.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self
+    }
+
+    // A board asked for by its seed should be that board, not the last session's
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.restore_autosave = false;
+        self
+    }
+
+    // The settings a run ended up with, once config.toml and the command line are applied
+    pub fn print_settings(&self) {
+        println!(
+            "Grid {}x{}, rule {}, {} neighbourhood, {}s per generation, density {}, seed {}",
+            self.config.grid_width,
+            self.config.grid_height,
+            self.ruleset,
+            self.neighborhood,
+            self.config.tick_rate,
+            self.density,
+            self.seed
+        );
+    }
+
+    // Steps the seeded grid `generations` times without a window, prints the timings and
+    // returns; none of the rendering or input systems run
+    pub fn run_headless(&self, generations: u64) {
+        App::new()
+            .add_plugins(MinimalPlugins)
+            .insert_resource(self.seeded_grid())
+            .insert_resource(HeadlessRun(match self.config.max_generations {
+                Some(max) => generations.min(max),
+                None => generations,
+            }))
+            .add_systems(Update, run_headless)
+            .run();
+    }
+
+    // Opens a window on an unbounded world seeded like the grid. The sparse world only
+    // stores live cells, so Generations rules lose their dying states.
+    pub fn run_infinite(self) {
+        let world = SparseGrid::from(&self.seeded_grid());
+        run_infinite(self.config, self.theme, world);
+    }
+
+    // An empty grid with these settings; `setup` seeds it
+    fn grid(&self) -> Grid {
+        let mut grid = Grid::new(self.config.grid_width, self.config.grid_height);
+        grid.ruleset = self.ruleset;
+        grid.neighborhood = self.neighborhood;
+        grid.cycle_window = self.cycle_window;
+        grid
+    }
+
+    // For the modes that don't run `setup`
+    fn seeded_grid(&self) -> Grid {
+        let mut grid = self.grid();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        seed_board(&mut grid, self.start.as_ref(), self.density, &mut rng);
+        grid
+    }
+}
+
+impl Plugin for GameOfLifePlugin {
+    fn build(&self, app: &mut App) {
+        // The FPS counter reads its diagnostics; the host app may already have added it
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        let config = self.config.clone();
+        app.insert_resource(self.grid())
+            .insert_resource(TickTimer(Timer::from_seconds(
+                config.tick_rate,
+                TimerMode::Repeating,
+            )))
+            .insert_resource(History::new(config.history_limit))
+            .insert_resource(config)
+            .insert_resource(SimState::Running)
+            .insert_resource(RandomConfig {
+                density: self.density,
+            })
+            .init_resource::<Brush>()
+            .init_resource::<InsertMode>()
+            .insert_resource(PatternLibrary::builtin())
+            .init_resource::<SelectedPattern>()
+            .init_resource::<ColorMode>()
+            .insert_resource(self.theme)
+            .insert_resource(FadeDeadCells(true))
+            .add_event::<GridResized>()
+            .insert_resource(RngSeed(self.seed))
+            .insert_resource(RngResource(StdRng::seed_from_u64(self.seed)))
+            .insert_resource(StartPattern(self.start.clone()))
+            .insert_resource(RestoreAutosave(self.restore_autosave))
+            .add_systems(Startup, setup)
+            .add_systems(Startup, setup_fps_counter)
+            .add_systems(Startup, setup_population_counter)
+            .add_systems(Startup, setup_generation_counter)
+            .add_systems(Startup, setup_tick_rate_counter)
+            .add_systems(Startup, setup_status_text)
+            .add_systems(Startup, setup_brush_text)
+            .add_systems(Startup, setup_seed_text)
+            .add_systems(Startup, setup_minimap)
+            .add_systems(Startup, setup_brush_preview)
+            .add_systems(Update, toggle_pause)
+            .add_systems(Update, toggle_topology)
+            .add_systems(Update, toggle_brians_brain)
+            .add_systems(Update, toggle_neighborhood)
+            .add_systems(Update, adjust_density)
+            .add_systems(
+                Update,
+                render_grid.after(camera_control).after(keyboard_pan),
+            )
+            .add_systems(Update, fade_dead_cells.after(render_grid))
+            .add_systems(Update, toggle_fade)
+            .add_systems(Update, assert_border_persists)
+            .add_systems(Update, camera_control)
+            .add_systems(Update, keyboard_pan)
+            .add_systems(Update, toggle_grid_lines)
+            .insert_resource(MinimapTimer(Timer::from_seconds(
+                MINIMAP_REFRESH,
+                TimerMode::Repeating,
+            )))
+            .add_systems(Update, toggle_minimap)
+            .add_systems(Update, update_minimap)
+            .add_systems(
+                Update,
+                update_minimap_viewport
+                    .after(camera_control)
+                    .after(keyboard_pan),
+            )
+            .add_systems(Update, toggle_color_mode)
+            .add_systems(Update, cycle_theme)
+            .add_systems(Update, adjust_brush)
+            .add_systems(Update, toggle_brush_shape)
+            .add_systems(Update, toggle_insert_mode)
+            .add_systems(Update, select_pattern)
+            .add_systems(Update, update_brush_preview)
+            .add_systems(Update, adjust_speed)
+            .add_systems(Update, update_fps_counter)
+            .add_systems(Update, update_population_counter::<Grid>)
+            .add_systems(Update, update_generation_counter::<Grid>)
+            .add_systems(Update, update_status_text)
+            .add_systems(Update, auto_pause.after(update_grid))
+            .add_systems(Update, stop_at_max_generations.before(update_grid));
+
+        match &self.replay {
+            Some(recording) => {
+                app.insert_resource(Replay {
+                    recording: recording.clone(),
+                    frame: 0,
+                })
+                .add_systems(Startup, start_replay.after(setup))
+                .add_systems(Update, play_replay.before(render_grid))
+                .add_systems(Update, scrub_replay.before(render_grid));
+            }
+            // A replay shows frames of a fixed size, so the grid can't be edited, resized or
+            // loaded over while it plays
+            None => {
+                app.add_systems(
+                    Update,
+                    update_grid.run_if(resource_equals(SimState::Running)),
+                )
+                .add_systems(Update, step_once.before(render_grid))
+                .add_systems(Update, rewind.before(render_grid))
+                .add_systems(Update, clear_grid)
+                .add_systems(Update, randomize_grid)
+                .add_systems(Update, undo_redo)
+                .add_systems(Update, nudge_grid.before(render_grid))
+                .add_systems(Update, transform_grid.before(render_grid))
+                .add_systems(Update, handle_clicks)
+                .add_systems(Update, stamp_at_cursor)
+                .add_systems(Update, (resize_grid, rebuild_board).chain());
+                // A replay's frames aren't the user's board, so only this mode autosaves on exit
+                #[cfg(not(target_arch = "wasm32"))]
+                app.add_systems(Update, load_grid.after(resize_grid).before(rebuild_board))
+                    .add_systems(Last, autosave_on_exit);
+            }
+        }
+
+        // Saving, loading and exporting need a filesystem, which browsers don't have, and
+        // arboard has no web clipboard
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, save_grid)
+            .add_systems(Update, export_rle)
+            .add_systems(Update, export_png)
+            .add_systems(Update, copy_seed)
+            .init_resource::<GifCapture>()
+            .add_systems(Update, record_gif.after(render_grid));
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = &self.record {
+            app.insert_resource(RecordPath(path.clone()))
+                .add_systems(Startup, start_recording.after(setup))
+                .add_systems(
+                    Update,
+                    record_generations
+                        .after(update_grid)
+                        .after(step_once)
+                        .after(rewind),
+                );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/recording.rs
+
+use std::fmt;
+
+// Every recording starts with these bytes, then the width and height as little-endian u32s
+const MAGIC: &[u8; 4] = b"GOLR";
+const HEADER_LEN: usize = MAGIC.len() + 8;
+
+// A run of generations read back from a recording, each frame laid out like `Grid::cells`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recording {
+    pub width: usize,
+    pub height: usize,
+    pub frames: Vec<Vec<bool>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecordingError {
+    MissingHeader,
+    InvalidSize(usize, usize),
+    NoFrames,
+    TruncatedFrame(usize), // The last frame, numbered from 0, is cut short
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordingError::MissingHeader => write!(f, "not a Game of Life recording"),
+            RecordingError::InvalidSize(width, height) => {
+                write!(f, "invalid board size {}x{}", width, height)
+            }
+            RecordingError::NoFrames => write!(f, "recording has no frames"),
+            RecordingError::TruncatedFrame(frame) => write!(f, "frame {} is cut short", frame),
+        }
+    }
+}
+
+// The bytes a recording of a width x height board starts with
+pub fn encode_header(width: usize, height: usize) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.extend_from_slice(&(width as u32).to_le_bytes());
+    header.extend_from_slice(&(height as u32).to_le_bytes());
+    header
+}
+
+// One frame, packed eight cells to a byte with the first cell in the lowest bit. Frames are
+// appended to the header as the run goes, so a recording can be cut off at any frame.
+pub fn encode_frame(cells: &[bool]) -> Vec<u8> {
+    cells
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (bit, &alive)| byte | ((alive as u8) << bit))
+        })
+        .collect()
+}
+
+pub fn parse_recording(bytes: &[u8]) -> Result<Recording, RecordingError> {
+    if bytes.len() < HEADER_LEN || !bytes.starts_with(MAGIC) {
+        return Err(RecordingError::MissingHeader);
+    }
+    let field = |at: usize| {
+        let mut le = [0; 4];
+        le.copy_from_slice(&bytes[at..at + 4]);
+        u32::from_le_bytes(le) as usize
+    };
+    let (width, height) = (field(MAGIC.len()), field(MAGIC.len() + 4));
+    if width == 0 || height == 0 {
+        return Err(RecordingError::InvalidSize(width, height));
+    }
+
+    let frame_len = (width * height).div_ceil(8);
+    let body = &bytes[HEADER_LEN..];
+    if !body.len().is_multiple_of(frame_len) {
+        return Err(RecordingError::TruncatedFrame(body.len() / frame_len));
+    }
+    let frames: Vec<Vec<bool>> = body
+        .chunks(frame_len)
+        .map(|frame| {
+            (0..width * height)
+                .map(|i| frame[i / 8] & (1 << (i % 8)) != 0)
+                .collect()
+        })
+        .collect();
+    if frames.is_empty() {
+        return Err(RecordingError::NoFrames);
+    }
+    Ok(Recording {
+        width,
+        height,
+        frames,
+    })
+}
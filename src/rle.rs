@@ -0,0 +1,164 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/rle.rs
+
+use crate::patterns::{cell_count, Pattern, MAX_PATTERN_CELLS};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RleError {
+    MissingHeader,
+    InvalidHeader(String),
+    UnexpectedChar(char),
+    OutOfBounds,            // Live cells beyond the size declared in the header
+    TooLarge(usize, usize), // The header's width and height, over MAX_PATTERN_CELLS
+    RunTooLong,             // A run count too large to be a real run
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "missing 'x = .., y = ..' header line"),
+            RleError::InvalidHeader(line) => write!(f, "invalid header line '{}'", line),
+            RleError::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            RleError::OutOfBounds => write!(f, "pattern is larger than its header declares"),
+            RleError::TooLarge(width, height) => write!(
+                f,
+                "{}x{} is larger than the {} cell limit",
+                width, height, MAX_PATTERN_CELLS
+            ),
+            RleError::RunTooLong => write!(f, "run count is too large"),
+        }
+    }
+}
+
+// Parses the run-length encoded format used by most Life software, e.g.
+//
+//     #N Glider
+//     x = 3, y = 3, rule = B3/S23
+//     bob$2bo$3o!
+pub fn parse_rle(input: &str) -> Result<Pattern, RleError> {
+    let mut lines = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    let header = lines.next().ok_or(RleError::MissingHeader)?;
+    let (width, height) = parse_header(header)?;
+
+    let size = cell_count(width, height).ok_or(RleError::TooLarge(width, height))?;
+    let mut cells = vec![false; size];
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut run: usize = 0; // Pending run count, 0 meaning a single cell
+    'body: for line in lines {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    run = run
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(c as usize - '0' as usize))
+                        .ok_or(RleError::RunTooLong)?;
+                }
+                'b' => {
+                    x = x.saturating_add(run.max(1));
+                    run = 0;
+                }
+                'o' => {
+                    let count = run.max(1);
+                    if x.saturating_add(count) > width || y >= height {
+                        return Err(RleError::OutOfBounds);
+                    }
+                    cells[y * width + x..y * width + x + count].fill(true);
+                    x += count;
+                    run = 0;
+                }
+                '$' => {
+                    y = y.saturating_add(run.max(1));
+                    x = 0;
+                    run = 0;
+                }
+                '!' => break 'body,
+                c if c.is_whitespace() => {}
+                _ => return Err(RleError::UnexpectedChar(c)),
+            }
+        }
+    }
+
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+// Reads the width and height from a line like `x = 3, y = 3, rule = B3/S23`
+fn parse_header(line: &str) -> Result<(usize, usize), RleError> {
+    let invalid = || RleError::InvalidHeader(line.to_string());
+    let mut width = None;
+    let mut height = None;
+    for field in line.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => width = Some(value.trim().parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.trim().parse().map_err(|_| invalid())?),
+            _ => {} // e.g. the rule, which the grid configures separately
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+// Encodes a pattern as RLE, trimming trailing dead cells and rows like other Life tools do
+pub fn to_rle(pattern: &Pattern, rule: &str) -> String {
+    let mut tokens = Vec::new();
+    let mut row_ends = 0; // `$` tokens owed, merged into one run before the next live row
+    for y in 0..pattern.height {
+        if y > 0 {
+            row_ends += 1;
+        }
+        let row = &pattern.cells[y * pattern.width..(y + 1) * pattern.width];
+        let Some(last_alive) = row.iter().rposition(|&c| c) else {
+            continue;
+        };
+        if row_ends > 0 {
+            tokens.push(run_token(row_ends, '$'));
+            row_ends = 0;
+        }
+        let mut x = 0;
+        while x <= last_alive {
+            let alive = row[x];
+            let run = row[x..=last_alive]
+                .iter()
+                .take_while(|&&c| c == alive)
+                .count();
+            tokens.push(run_token(run, if alive { 'o' } else { 'b' }));
+            x += run;
+        }
+    }
+    tokens.push("!".to_string());
+
+    // Keep body lines under the 70 characters the format recommends
+    let mut out = format!(
+        "x = {}, y = {}, rule = {}\n",
+        pattern.width, pattern.height, rule
+    );
+    let mut line_len = 0;
+    for token in tokens {
+        if line_len + token.len() > 70 {
+            out.push('\n');
+            line_len = 0;
+        }
+        line_len += token.len();
+        out.push_str(&token);
+    }
+    out.push('\n');
+    out
+}
+
+fn run_token(count: usize, tag: char) -> String {
+    if count > 1 {
+        format!("{}{}", count, tag)
+    } else {
+        tag.to_string()
+    }
+}
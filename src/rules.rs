@@ -0,0 +1,172 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/rules.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+// Decides a cell's next state from its current one and how many live neighbours it has.
+// `Grid::step_with` is generic over this, so each automaton gets its own monomorphized
+// loop. `Ruleset` is the implementor `step` uses.
+pub trait CellularAutomaton {
+    type Cell;
+
+    fn next_state(&self, cell: Self::Cell, neighbors: usize) -> Self::Cell;
+}
+
+// Birth/survival rule in B/S notation, indexed by live neighbor count. With more than two
+// states it is a Generations rule: a live cell that doesn't survive spends `states - 2`
+// generations dying, during which it neither counts as a neighbour nor can be reborn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ruleset {
+    pub birth: [bool; 9],
+    pub survive: [bool; 9],
+    pub states: u8, // 2 for classic rules such as Conway's
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    MissingSlash,
+    MissingPrefix(char),
+    InvalidCount(char),
+    InvalidStates(String),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleParseError::MissingSlash => {
+                write!(f, "expected a '/' between birth and survival counts")
+            }
+            RuleParseError::MissingPrefix(prefix) => {
+                write!(f, "expected counts to start with '{}'", prefix)
+            }
+            RuleParseError::InvalidCount(c) => {
+                write!(f, "'{}' is not a neighbor count between 0 and 8", c)
+            }
+            RuleParseError::InvalidStates(states) => {
+                write!(f, "'{}' is not a state count between 2 and 255", states)
+            }
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        // Conway's Game of Life: B3/S23
+        let mut birth = [false; 9];
+        let mut survive = [false; 9];
+        birth[3] = true;
+        survive[2] = true;
+        survive[3] = true;
+        Self {
+            birth,
+            survive,
+            states: 2,
+        }
+    }
+}
+
+impl Ruleset {
+    // Brian's Brain, B2/S/C3: an off cell turns on with exactly two on neighbours, and
+    // every on cell spends one generation dying before it turns off
+    pub fn brians_brain() -> Self {
+        let mut birth = [false; 9];
+        birth[2] = true;
+        Self {
+            birth,
+            survive: [false; 9],
+            states: 3,
+        }
+    }
+
+    // Accepts `B3/S23`, with an optional state count for Generations rules (`B2/S345/C4`),
+    // and the survival-first Generations notation, e.g. `345/2/4` or `/2/3`
+    pub fn parse(rule: &str) -> Result<Self, RuleParseError> {
+        let parts: Vec<&str> = rule.trim().splitn(3, '/').collect();
+        let (birth, survive, states) = match parts[..] {
+            [birth, survive] => (parse_counts(birth, 'B')?, parse_counts(survive, 'S')?, 2),
+            [birth, survive, states] if birth.starts_with(['B', 'b']) => (
+                parse_counts(birth, 'B')?,
+                parse_counts(survive, 'S')?,
+                parse_states(states)?,
+            ),
+            [survive, birth, states] => (
+                parse_digits(birth)?,
+                parse_digits(survive)?,
+                parse_states(states)?,
+            ),
+            _ => return Err(RuleParseError::MissingSlash),
+        };
+        Ok(Self {
+            birth,
+            survive,
+            states,
+        })
+    }
+
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        let counts = if alive { &self.survive } else { &self.birth };
+        counts.get(neighbors).copied().unwrap_or(false)
+    }
+}
+
+impl CellularAutomaton for Ruleset {
+    type Cell = bool;
+
+    fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        Ruleset::next_state(self, alive, neighbors)
+    }
+}
+
+// So rules can be read with `str::parse`, e.g. `"B36/S23".parse::<Ruleset>()`
+impl FromStr for Ruleset {
+    type Err = RuleParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        Ruleset::parse(rule)
+    }
+}
+
+impl fmt::Display for Ruleset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| counts[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survive))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_counts(part: &str, prefix: char) -> Result<[bool; 9], RuleParseError> {
+    let digits = part
+        .strip_prefix(prefix)
+        .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+        .ok_or(RuleParseError::MissingPrefix(prefix))?;
+    parse_digits(digits)
+}
+
+fn parse_digits(digits: &str) -> Result<[bool; 9], RuleParseError> {
+    let mut counts = [false; 9];
+    for c in digits.chars() {
+        match c.to_digit(10) {
+            Some(n) if n <= 8 => counts[n as usize] = true,
+            _ => return Err(RuleParseError::InvalidCount(c)),
+        }
+    }
+    Ok(counts)
+}
+
+// The `4` or `C4` at the end of a Generations rule
+fn parse_states(part: &str) -> Result<u8, RuleParseError> {
+    let count = part.strip_prefix(['C', 'c']).unwrap_or(part);
+    match count.parse() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(RuleParseError::InvalidStates(part.to_string())),
+    }
+}
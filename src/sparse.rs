@@ -0,0 +1,116 @@
+// Package: rusty_game_of_life_with_bevy
+// File: src/sparse.rs
+
+use crate::grid::{Grid, Neighborhood};
+use crate::rules::Ruleset;
+use std::collections::{HashMap, HashSet};
+
+// An unbounded board storing only its live cells, for huge mostly-dead worlds. Stepping
+// only looks at live cells and their neighbours, so its cost follows the population rather
+// than the area. Rules with B0 would bring the infinite dead background to life, so births
+// always need at least one live neighbour here. Cells are only ever alive or dead, so a
+// Generations rule's state count is ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Resource))]
+pub struct SparseGrid {
+    pub live: HashSet<(i64, i64)>,
+    pub ruleset: Ruleset,
+    pub neighborhood: Neighborhood,
+    pub generation: u64,
+}
+
+impl SparseGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        self.live.contains(&(x, y))
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: bool) {
+        if value {
+            self.live.insert((x, y));
+        } else {
+            self.live.remove(&(x, y));
+        }
+    }
+
+    pub fn toggle(&mut self, x: i64, y: i64) {
+        let value = !self.get(x, y);
+        self.set(x, y, value);
+    }
+
+    pub fn neighbors(&self, x: i64, y: i64) -> usize {
+        self.offsets()
+            .filter(|&(dx, dy)| self.get(x + dx, y + dy))
+            .count()
+    }
+
+    // Where each neighbour of a cell is, relative to it
+    fn offsets(&self) -> impl Iterator<Item = (i64, i64)> {
+        let neighborhood = self.neighborhood;
+        let range = neighborhood.range() as i64;
+        (-range..=range)
+            .flat_map(move |dx| (-range..=range).map(move |dy| (dx, dy)))
+            .filter(move |&(dx, dy)| neighborhood.contains(dx as isize, dy as isize))
+    }
+
+    pub fn step(&mut self) {
+        // Every cell with a live neighbour, and how many it has
+        let offsets: Vec<_> = self.offsets().collect();
+        let mut counts: HashMap<(i64, i64), usize> = HashMap::new();
+        for &(x, y) in &self.live {
+            for &(dx, dy) in &offsets {
+                *counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+            }
+        }
+
+        let mut next: HashSet<(i64, i64)> = counts
+            .iter()
+            .filter(|&(&cell, &count)| self.ruleset.next_state(self.live.contains(&cell), count))
+            .map(|(&cell, _)| cell)
+            .collect();
+        // Isolated cells never show up in `counts` but may survive under S0 rules
+        if self.ruleset.survive[0] {
+            next.extend(self.live.iter().filter(|cell| !counts.contains_key(cell)));
+        }
+        self.live = next;
+        self.generation += 1;
+    }
+
+    pub fn count_live(&self) -> usize {
+        self.live.len()
+    }
+
+    // The smallest (min_x, min_y, max_x, max_y) box holding every live cell
+    pub fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut cells = self.live.iter();
+        let &(x, y) = cells.next()?;
+        Some(
+            cells.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), &(x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            }),
+        )
+    }
+}
+
+// Keeps the dense grid's coordinates, with (0, 0) as its first cell
+impl From<&Grid> for SparseGrid {
+    fn from(grid: &Grid) -> Self {
+        let mut sparse = SparseGrid {
+            ruleset: grid.ruleset,
+            neighborhood: grid.neighborhood,
+            generation: grid.generation,
+            ..Default::default()
+        };
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if grid.get(x, y) {
+                    sparse.live.insert((x as i64, y as i64));
+                }
+            }
+        }
+        sparse
+    }
+}
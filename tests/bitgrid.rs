@@ -0,0 +1,44 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/bitgrid.rs
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusty_game_of_life_with_bevy::{BitGrid, Grid, Topology};
+
+#[test]
+fn get_and_set_across_word_boundaries() {
+    // 10x13 = 130 cells, so the last word is only partly used
+    let mut bits = BitGrid::new(10, 13);
+    let boundary_cells = [(3, 6), (4, 6), (7, 12), (8, 12), (9, 12), (0, 0)];
+    for &(x, y) in &boundary_cells {
+        bits.set(x, y, true);
+    }
+    for y in 0..13 {
+        for x in 0..10 {
+            let expected = boundary_cells.contains(&(x, y));
+            assert_eq!(bits.get(x, y), expected, "({}, {})", x, y);
+        }
+    }
+    assert_eq!(bits.count_live(), boundary_cells.len());
+
+    bits.set(3, 6, false); // Bit 63 of the first word
+    bits.toggle(4, 6); // Bit 0 of the second
+    bits.toggle(5, 6);
+    assert!(!bits.get(3, 6) && !bits.get(4, 6) && bits.get(5, 6));
+}
+
+#[test]
+fn steps_like_the_dense_grid() {
+    for topology in [Topology::Bounded, Topology::Toroidal] {
+        let mut grid = Grid::with_topology(37, 29, topology);
+        grid.randomize(0.35, &mut StdRng::seed_from_u64(3));
+        let mut bits = BitGrid::from(&grid);
+
+        for _ in 0..25 {
+            grid.step();
+            bits.step();
+            assert_eq!(Grid::from(&bits).cells, grid.cells);
+            assert_eq!(bits.count_live(), grid.count_live());
+        }
+    }
+}
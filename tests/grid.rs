@@ -0,0 +1,223 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/grid.rs
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusty_game_of_life_with_bevy::{Grid, History, NotSquare, Topology};
+use std::{fs, io};
+
+#[test]
+fn wrap_clips_bounded_grids() {
+    let grid = Grid::new(4, 3);
+    assert_eq!(grid.wrap(0, 0), Some((0, 0)));
+    assert_eq!(grid.wrap(3, 2), Some((3, 2)));
+    assert_eq!(grid.wrap(-1, 0), None);
+    assert_eq!(grid.wrap(4, 0), None);
+    assert_eq!(grid.wrap(0, 3), None);
+}
+
+#[test]
+fn wrap_joins_opposite_edges_of_toroidal_grids() {
+    let mut grid = Grid::with_topology(4, 3, Topology::Toroidal);
+    assert_eq!(grid.wrap(-1, 0), Some((3, 0)));
+    assert_eq!(grid.wrap(4, -1), Some((0, 2)));
+    assert_eq!(grid.wrap(9, 7), Some((1, 1)));
+
+    // Toggling the topology changes where the same offsets land
+    grid.topology = Topology::Bounded;
+    assert_eq!(grid.wrap(-1, 0), None);
+}
+
+#[test]
+fn randomize_is_reproducible_from_a_seed() {
+    let (mut first, mut second) = (Grid::new(40, 30), Grid::new(40, 30));
+    first.randomize(0.2, &mut StdRng::seed_from_u64(42));
+    second.randomize(0.2, &mut StdRng::seed_from_u64(42));
+    assert_eq!(first.cells, second.cells);
+
+    second.randomize(0.2, &mut StdRng::seed_from_u64(43));
+    assert_ne!(first.cells, second.cells);
+}
+
+#[test]
+fn shifting_a_toroidal_grid_there_and_back_restores_it() {
+    let mut grid = Grid::with_topology(6, 5, Topology::Toroidal);
+    for (x, y) in [(2, 2), (3, 2), (4, 2), (5, 3)] {
+        grid.set(x, y, true);
+    }
+    let start = grid.cells.clone();
+
+    grid.shift_grid(1, 0);
+    assert!(
+        grid.get(0, 3),
+        "the cell on the right edge wraps to the left"
+    );
+    assert!(grid.get(3, 2) && !grid.get(2, 2));
+    grid.shift_grid(-1, 0);
+    assert_eq!(grid.cells, start);
+
+    grid.shift_grid(0, -3);
+    grid.shift_grid(0, 3);
+    assert_eq!(grid.cells, start);
+}
+
+#[test]
+fn shifting_a_bounded_grid_drops_cells_off_the_edge() {
+    let mut grid = Grid::new(4, 4);
+    grid.set(3, 1, true);
+    grid.set(1, 1, true);
+    grid.shift_grid(1, 1);
+    assert!(grid.get(2, 2));
+    assert_eq!(grid.count_live(), 1);
+}
+
+#[test]
+fn rotating_four_times_restores_the_board() {
+    // An R-pentomino has no symmetry, so every quarter turn looks different
+    let mut grid = Grid::new(7, 7);
+    for (x, y) in [(2, 1), (3, 1), (3, 2), (4, 2), (3, 3)] {
+        grid.set(x, y, true);
+    }
+    let start = grid.cells.clone();
+
+    let mut turns = Vec::new();
+    for _ in 0..4 {
+        grid.rotate_cw().unwrap();
+        turns.push(grid.cells.clone());
+    }
+    assert_eq!(grid.cells, start);
+    assert!(turns[..3].iter().all(|turn| *turn != start));
+}
+
+#[test]
+fn rotating_turns_clockwise_as_drawn() {
+    // y grows upwards, so the right edge turns to the bottom
+    let mut grid = Grid::new(5, 5);
+    grid.set(4, 3, true);
+    grid.rotate_cw().unwrap();
+    assert!(grid.get(3, 0));
+    assert_eq!(grid.count_live(), 1);
+}
+
+#[test]
+fn rotating_a_non_square_grid_fails_and_leaves_it_alone() {
+    let mut grid = Grid::new(6, 4);
+    grid.set(1, 0, true);
+    let start = grid.cells.clone();
+    assert_eq!(grid.rotate_cw(), Err(NotSquare(6, 4)));
+    assert_eq!(grid.cells, start);
+}
+
+#[test]
+fn flipping_mirrors_the_board_and_flipping_again_restores_it() {
+    let mut grid = Grid::new(5, 4);
+    grid.set(0, 1, true);
+    grid.set(3, 3, true);
+    let start = grid.cells.clone();
+
+    grid.flip_h();
+    assert!(grid.get(4, 1) && grid.get(1, 3));
+    grid.flip_h();
+    assert_eq!(grid.cells, start);
+
+    grid.flip_v();
+    assert!(grid.get(0, 2) && grid.get(3, 0));
+    grid.flip_v();
+    assert_eq!(grid.cells, start);
+}
+
+#[test]
+fn loading_a_save_with_a_huge_size_fails_before_allocating() {
+    let path = std::env::temp_dir().join("rusty_game_of_life_huge_header.gol");
+    for header in ["100000 100000", "18446744073709551615 2"] {
+        fs::write(&path, format!("{}\n", header)).unwrap();
+        let Err(err) = Grid::load_from_path(&path) else {
+            panic!("loaded a {} grid", header);
+        };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", header);
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn undo_after_a_step_restores_the_earlier_board() {
+    let mut grid = Grid::new(5, 5);
+    for x in 1..4 {
+        grid.set(x, 2, true);
+    }
+    grid.step();
+    let (before, before_age) = (grid.cells.clone(), grid.age.clone());
+
+    let mut history = History::new(10);
+    history.record(&grid);
+    grid.step();
+    assert_ne!(grid.cells, before);
+
+    assert!(history.undo(&mut grid));
+    assert_eq!(grid.cells, before);
+    assert_eq!(grid.age, before_age, "ages come back with the cells");
+    assert_eq!(grid.generation, 1);
+    assert!(!history.undo(&mut grid));
+
+    assert!(history.redo(&mut grid));
+    assert_eq!(grid.generation, 2);
+    assert_ne!(grid.cells, before);
+}
+
+#[test]
+fn history_keeps_only_the_most_recent_snapshots() {
+    let mut grid = Grid::new(4, 4);
+    let mut history = History::new(2);
+    for _ in 0..5 {
+        history.record(&grid);
+        grid.step();
+    }
+    assert_eq!(history.undo_count(), 2);
+    while history.undo(&mut grid) {}
+    assert_eq!(grid.generation, 3);
+
+    // A new edit drops whatever was undone
+    history.record(&grid);
+    assert_eq!(history.redo_count(), 0);
+}
+
+#[test]
+fn history_with_a_limit_of_zero_keeps_nothing() {
+    let mut grid = Grid::new(4, 4);
+    let mut history = History::new(0);
+    history.record(&grid);
+    grid.step();
+
+    assert_eq!(history.undo_count(), 0);
+    assert!(!history.undo(&mut grid));
+    assert_eq!(grid.generation, 1);
+}
+
+#[test]
+fn history_stays_within_its_memory_limit() {
+    // Each 300x300 snapshot takes over half a megabyte, so only one fits
+    let mut grid = Grid::new(300, 300);
+    let mut history = History::with_max_bytes(100, 1 << 20);
+    for _ in 0..5 {
+        history.record(&grid);
+        grid.step();
+    }
+    assert_eq!(history.undo_count(), 1);
+}
+
+#[test]
+fn undo_and_redo_keep_the_history_within_its_limit() {
+    let mut grid = Grid::new(4, 4);
+    let mut history = History::new(2);
+    for _ in 0..3 {
+        history.record(&grid);
+        grid.step();
+    }
+    assert!(history.undo(&mut grid));
+    assert!(history.redo(&mut grid));
+    assert_eq!(history.undo_count(), 2);
+
+    // Undoing twice reaches the oldest snapshot that was kept
+    while history.undo(&mut grid) {}
+    assert_eq!(grid.generation, 1);
+}
@@ -0,0 +1,43 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/life106.rs
+
+use rusty_game_of_life_with_bevy::life106::{parse_life106, to_life106, to_pattern, Life106Error};
+
+#[test]
+fn round_trips_a_glider() {
+    let glider = [(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)];
+    let pattern = to_pattern(&glider).unwrap();
+    assert_eq!((pattern.width, pattern.height), (3, 3));
+
+    // Written back out relative to the top-left corner, so shifted by (1, 1)
+    let written = to_life106(&pattern);
+    assert!(written.starts_with("#Life 1.06\n"));
+    let mut parsed = parse_life106(&written).unwrap();
+    parsed.sort_by_key(|&(x, y)| (y, x));
+    assert_eq!(parsed, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert_eq!(to_pattern(&parsed), Ok(pattern));
+}
+
+#[test]
+fn skips_comments_and_rejects_malformed_lines() {
+    assert_eq!(
+        parse_life106("#Life 1.06\n#D note\n3 4\n"),
+        Ok(vec![(3, 4)])
+    );
+    assert_eq!(
+        parse_life106("#Life 1.06\n1 2 3\n"),
+        Err(Life106Error::InvalidLine("1 2 3".to_string()))
+    );
+}
+
+#[test]
+fn rejects_cells_too_far_apart() {
+    assert_eq!(
+        to_pattern(&[(0, 0), (1_000_000_000, 0)]),
+        Err(Life106Error::TooLarge)
+    );
+    assert_eq!(
+        to_pattern(&[(i64::MIN, 0), (i64::MAX, 0)]),
+        Err(Life106Error::TooLarge)
+    );
+}
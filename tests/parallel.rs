@@ -0,0 +1,35 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/parallel.rs
+
+#![cfg(feature = "parallel")]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusty_game_of_life_with_bevy::{Grid, Topology};
+
+fn assert_parallel_matches(topology: Topology) {
+    // The same seed gives both grids the same random board
+    let mut sequential = Grid::with_topology(64, 48, topology);
+    sequential.randomize(0.3, &mut StdRng::seed_from_u64(7));
+    let mut parallel = Grid::with_topology(64, 48, topology);
+    parallel.randomize(0.3, &mut StdRng::seed_from_u64(7));
+    assert_eq!(parallel.cells, sequential.cells);
+
+    for _ in 0..20 {
+        sequential.step();
+        parallel.step_parallel();
+        assert_eq!(parallel.cells, sequential.cells);
+        assert_eq!(parallel.prev_cells, sequential.prev_cells);
+        assert_eq!(parallel.generation, sequential.generation);
+    }
+}
+
+#[test]
+fn step_parallel_matches_step_on_a_bounded_grid() {
+    assert_parallel_matches(Topology::Bounded);
+}
+
+#[test]
+fn step_parallel_matches_step_on_a_toroidal_grid() {
+    assert_parallel_matches(Topology::Toroidal);
+}
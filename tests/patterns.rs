@@ -0,0 +1,142 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/patterns.rs
+
+use rusty_game_of_life_with_bevy::patterns::{parse_cells, CellsError, MAX_PATTERN_CELLS};
+use rusty_game_of_life_with_bevy::{Grid, PatternLibrary};
+
+#[test]
+fn builtin_patterns_have_their_usual_shapes() {
+    let shapes: Vec<_> = PatternLibrary::builtin()
+        .patterns
+        .iter()
+        .map(|(name, pattern)| {
+            let live = pattern.cells.iter().filter(|&&c| c).count();
+            (*name, pattern.width, pattern.height, live)
+        })
+        .collect();
+    assert_eq!(
+        shapes,
+        [
+            ("glider", 3, 3, 5),
+            ("LWSS", 5, 4, 9),
+            ("blinker", 3, 1, 3),
+            ("toad", 4, 2, 6),
+            ("pulsar", 13, 13, 48),
+            ("Gosper glider gun", 36, 9, 36),
+            ("MWSS", 6, 5, 11),
+            ("HWSS", 7, 5, 13),
+        ]
+    );
+}
+
+#[test]
+fn cells_files_skip_comments_and_pad_short_rows() {
+    let glider = "!Name: Glider\n!\n.O\n..O\nOOO\n\n";
+    let pattern = parse_cells(glider).unwrap();
+
+    assert_eq!((pattern.width, pattern.height), (3, 3));
+    #[rustfmt::skip]
+    let expected = vec![
+        false, true,  false,
+        false, false, true,
+        true,  true,  true,
+    ];
+    assert_eq!(pattern.cells, expected);
+}
+
+#[test]
+fn cells_files_larger_than_the_cell_limit_are_rejected() {
+    let row = "O".repeat(MAX_PATTERN_CELLS / 2 + 1);
+    let input = format!("{}\n.\n", row);
+
+    let err = parse_cells(&input).unwrap_err();
+    assert_eq!(err, CellsError::TooLarge(row.len(), 2));
+}
+
+#[test]
+fn pulsar_has_period_three() {
+    let library = PatternLibrary::builtin();
+    let pulsar = library.get("pulsar").unwrap();
+    let mut grid = Grid::new_square(17);
+    grid.insert_pattern(2, 14, pulsar);
+    let start = grid.cells.clone();
+
+    for generation in 1..=3 {
+        grid.step();
+        let repeated = grid.cells == start;
+        assert_eq!(repeated, generation == 3, "generation {}", generation);
+    }
+}
+
+// Live cells outside the given box, which anything leaving the pattern ends up in
+fn escaped(grid: &Grid, xs: std::ops::Range<usize>, ys: std::ops::Range<usize>) -> usize {
+    let mut count = 0;
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if grid.get(x, y) && !(xs.contains(&x) && ys.contains(&y)) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn glider_gun_fires_a_glider_every_thirty_generations() {
+    let library = PatternLibrary::builtin();
+    let gun = library.get("Gosper glider gun").unwrap();
+    let mut grid = Grid::new(60, 40);
+    grid.insert_pattern(2, 37, gun); // Occupies x 2..38 and y 29..38
+    let start = grid.cells.clone();
+
+    for _ in 0..30 {
+        grid.step();
+    }
+    // The gun is back as it was, plus one glider on its way out
+    assert!(start.iter().zip(&grid.cells).all(|(&was, &is)| !was || is));
+    assert_eq!(
+        grid.count_live(),
+        gun.cells.iter().filter(|&&c| c).count() + 5
+    );
+
+    for _ in 0..30 {
+        grid.step();
+    }
+    assert!(escaped(&grid, 2..38, 29..38) >= 5);
+}
+
+#[test]
+fn spaceships_move_two_cells_every_four_generations() {
+    let library = PatternLibrary::builtin();
+    for name in ["LWSS", "MWSS", "HWSS"] {
+        let ship = library.get(name).unwrap();
+        let mut grid = Grid::new(30, 12);
+        grid.insert_pattern(20, 8, ship);
+        let start = grid.cells.clone();
+        for _ in 0..4 {
+            grid.step();
+        }
+        assert_eq!(
+            grid.count_live(),
+            ship.cells.iter().filter(|&&c| c).count(),
+            "{}",
+            name
+        );
+        assert_ne!(grid.cells, start, "{}", name);
+        let shifted = |dx: isize| {
+            (0..grid.height).all(|y| {
+                (0..grid.width).all(|x| {
+                    let from = x as isize - dx;
+                    let inside = from >= 0 && (from as usize) < grid.width;
+                    let was = inside && start[y * grid.width + from as usize];
+                    grid.get(x, y) == was
+                })
+            })
+        };
+        assert!(
+            shifted(-2) || shifted(2),
+            "{} didn't move two cells sideways",
+            name
+        );
+    }
+}
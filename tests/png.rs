@@ -0,0 +1,25 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/png.rs
+
+#![cfg(feature = "png")]
+
+use rusty_game_of_life_with_bevy::Grid;
+
+const ALIVE: [u8; 4] = [255, 255, 255, 255];
+const DEAD: [u8; 4] = [0, 0, 0, 255];
+
+#[test]
+fn render_to_image_draws_a_block_per_cell_top_row_first() {
+    let mut grid = Grid::new(3, 2);
+    grid.set(0, 1, true); // Top-left on screen
+    grid.set(2, 0, true); // Bottom-right
+    let image = grid.render_to_image(2, |x, y| if grid.get(x, y) { ALIVE } else { DEAD });
+
+    assert_eq!(image.dimensions(), (6, 4));
+    for (px, py) in [(0, 0), (1, 1), (4, 2), (5, 3)] {
+        assert_eq!(image.get_pixel(px, py).0, ALIVE, "pixel ({}, {})", px, py);
+    }
+    for (px, py) in [(2, 0), (0, 2), (4, 1), (3, 3)] {
+        assert_eq!(image.get_pixel(px, py).0, DEAD, "pixel ({}, {})", px, py);
+    }
+}
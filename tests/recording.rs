@@ -0,0 +1,49 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/recording.rs
+
+use rusty_game_of_life_with_bevy::recording::{
+    encode_frame, encode_header, parse_recording, RecordingError,
+};
+use rusty_game_of_life_with_bevy::Grid;
+
+#[test]
+fn round_trips_a_run_of_generations() {
+    // 5x3 boards don't fill their last byte, so the padding is exercised too
+    let mut grid = Grid::new(5, 3);
+    for x in 1..4 {
+        grid.set(x, 1, true);
+    }
+    let mut bytes = encode_header(grid.width, grid.height);
+    let mut frames = Vec::new();
+    for _ in 0..3 {
+        bytes.extend(encode_frame(&grid.cells));
+        frames.push(grid.cells.clone());
+        grid.step();
+    }
+
+    let recording = parse_recording(&bytes).unwrap();
+    assert_eq!((recording.width, recording.height), (5, 3));
+    assert_eq!(recording.frames, frames);
+    assert_ne!(recording.frames[0], recording.frames[1]);
+}
+
+#[test]
+fn rejects_malformed_recordings() {
+    assert_eq!(parse_recording(b"GOL"), Err(RecordingError::MissingHeader));
+    assert_eq!(
+        parse_recording(&encode_header(0, 4)),
+        Err(RecordingError::InvalidSize(0, 4))
+    );
+    assert_eq!(
+        parse_recording(&encode_header(4, 4)),
+        Err(RecordingError::NoFrames)
+    );
+
+    let mut bytes = encode_header(4, 4);
+    bytes.extend(encode_frame(&[true; 16]));
+    bytes.push(0); // Half of a second frame
+    assert_eq!(
+        parse_recording(&bytes),
+        Err(RecordingError::TruncatedFrame(1))
+    );
+}
@@ -0,0 +1,73 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/rle.rs
+
+use rusty_game_of_life_with_bevy::rle::{parse_rle, to_rle, RleError};
+use rusty_game_of_life_with_bevy::{Grid, Pattern};
+
+#[test]
+fn parses_the_glider() {
+    let glider = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+    let pattern = parse_rle(glider).unwrap();
+
+    assert_eq!((pattern.width, pattern.height), (3, 3));
+    #[rustfmt::skip]
+    let expected = vec![
+        false, true,  false,
+        false, false, true,
+        true,  true,  true,
+    ];
+    assert_eq!(pattern.cells, expected);
+}
+
+#[test]
+fn rejects_cells_outside_the_header_size() {
+    assert_eq!(parse_rle("x = 2, y = 1\n3o!"), Err(RleError::OutOfBounds));
+}
+
+#[test]
+fn rejects_huge_headers_before_allocating() {
+    assert_eq!(
+        parse_rle("x = 100000, y = 100000\no!"),
+        Err(RleError::TooLarge(100000, 100000))
+    );
+    let overflowing = format!("x = {}, y = 2\no!", usize::MAX);
+    assert_eq!(
+        parse_rle(&overflowing),
+        Err(RleError::TooLarge(usize::MAX, 2))
+    );
+}
+
+#[test]
+fn rejects_runs_that_overflow() {
+    let body = format!("x = 3, y = 1\n{}o!", "9".repeat(40));
+    assert_eq!(parse_rle(&body), Err(RleError::RunTooLong));
+}
+
+#[test]
+fn encodes_runs_and_trims_trailing_dead_cells_and_rows() {
+    #[rustfmt::skip]
+    let cells = vec![
+        false, true,  false, false,
+        false, false, false, false,
+        true,  true,  false, false,
+        false, false, false, false,
+    ];
+    let pattern = Pattern {
+        width: 4,
+        height: 4,
+        cells,
+    };
+    let rle = to_rle(&pattern, "B3/S23");
+    assert_eq!(rle, "x = 4, y = 4, rule = B3/S23\nbo2$2o!\n");
+    assert_eq!(parse_rle(&rle), Ok(pattern));
+}
+
+#[test]
+fn round_trips_a_grid() {
+    let mut grid = Grid::new(12, 7);
+    for (x, y) in [(0, 0), (1, 0), (2, 0), (11, 3), (5, 6), (6, 6)] {
+        grid.set(x, y, true);
+    }
+    let pattern = grid.to_pattern();
+    assert_eq!(parse_rle(&grid.to_rle()), Ok(pattern));
+}
@@ -0,0 +1,51 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/rules.rs
+
+use rusty_game_of_life_with_bevy::{RuleParseError, Ruleset};
+use std::str::FromStr;
+
+#[test]
+fn from_str_matches_parse() {
+    assert_eq!(Ruleset::from_str("B3/S23"), Ok(Ruleset::default()));
+    assert_eq!("B2/S/C3".parse::<Ruleset>(), Ok(Ruleset::brians_brain()));
+}
+
+#[test]
+fn rejects_out_of_range_neighbour_counts() {
+    assert_eq!(
+        Ruleset::from_str("B9/S"),
+        Err(RuleParseError::InvalidCount('9'))
+    );
+    assert_eq!(
+        Ruleset::from_str("B3/S2x"),
+        Err(RuleParseError::InvalidCount('x'))
+    );
+}
+
+#[test]
+fn rejects_a_missing_slash() {
+    assert_eq!(
+        Ruleset::from_str("B3S23"),
+        Err(RuleParseError::MissingSlash)
+    );
+}
+
+#[test]
+fn rejects_a_missing_prefix() {
+    assert_eq!(
+        Ruleset::from_str("3/S23"),
+        Err(RuleParseError::MissingPrefix('B'))
+    );
+    assert_eq!(
+        Ruleset::from_str("B3/23"),
+        Err(RuleParseError::MissingPrefix('S'))
+    );
+}
+
+#[test]
+fn rejects_fewer_than_two_states() {
+    assert_eq!(
+        Ruleset::from_str("B3/S23/C1"),
+        Err(RuleParseError::InvalidStates("C1".to_string()))
+    );
+}
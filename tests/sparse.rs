@@ -0,0 +1,77 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/sparse.rs
+
+use rusty_game_of_life_with_bevy::{Grid, Life, Neighborhood, Ruleset, SparseGrid};
+
+// The live cells of a board, in a fixed order so boards can be compared
+fn live_cells(board: &impl Life, width: i64, height: i64) -> Vec<(i64, i64)> {
+    let mut live = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if board.get(x, y) {
+                live.push((x, y));
+            }
+        }
+    }
+    live
+}
+
+#[test]
+fn sparse_and_dense_grids_agree_on_a_glider() {
+    // Heading towards +x, -y, so started high enough to stay clear of the dense grid's edge
+    let glider = [(1, 11), (2, 11), (3, 11), (3, 12), (2, 13)];
+    let mut dense = Grid::new_square(20);
+    let mut sparse = SparseGrid::new();
+    for &(x, y) in &glider {
+        Life::set(&mut dense, x, y, true);
+        sparse.set(x, y, true);
+    }
+
+    for _ in 0..10 {
+        Life::step(&mut dense);
+        sparse.step();
+        assert_eq!(live_cells(&dense, 20, 20), live_cells(&sparse, 20, 20));
+        assert_eq!(Life::count_live(&dense), sparse.count_live());
+    }
+    assert_eq!(sparse.generation, 10);
+}
+
+#[test]
+fn sparse_grid_has_no_edges() {
+    // A blinker straddling the origin keeps oscillating into negative coordinates
+    let mut sparse = SparseGrid::new();
+    for x in -1..=1 {
+        sparse.set(x, 0, true);
+    }
+    sparse.step();
+    assert!(sparse.get(0, -1) && sparse.get(0, 0) && sparse.get(0, 1));
+    assert_eq!(sparse.count_live(), 3);
+    assert_eq!(sparse.bounds(), Some((0, -1, 0, 1)));
+}
+
+#[test]
+fn sparse_grid_converts_from_a_dense_grid() {
+    let mut dense = Grid::new_square(5);
+    dense.set(4, 2, true);
+    let sparse = SparseGrid::from(&dense);
+    assert!(sparse.get(4, 2));
+    assert_eq!(sparse.count_live(), 1);
+}
+
+#[test]
+fn sparse_grids_step_the_dense_grids_neighbourhood() {
+    let mut dense = Grid::new_square(30);
+    dense.neighborhood = Neighborhood::VonNeumann;
+    dense.ruleset = Ruleset::parse("B1/S1234").unwrap();
+    Life::set(&mut dense, 15, 15, true);
+    let mut sparse = SparseGrid::from(&dense);
+    assert_eq!(sparse.neighborhood, Neighborhood::VonNeumann);
+
+    // Growing a diamond, which a Moore neighbourhood would make a square
+    for _ in 0..5 {
+        dense.step();
+        sparse.step();
+        assert_eq!(live_cells(&dense, 30, 30), live_cells(&sparse, 30, 30));
+    }
+    assert!(!sparse.get(16, 16));
+}
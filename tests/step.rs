@@ -0,0 +1,418 @@
+// Package: rusty_game_of_life_with_bevy
+// File: tests/step.rs
+
+use rusty_game_of_life_with_bevy::rle::parse_rle;
+use rusty_game_of_life_with_bevy::{
+    CellularAutomaton, Grid, Neighborhood, PatternLibrary, Ruleset, Topology,
+};
+
+fn grid_with(size: usize, live: &[(usize, usize)]) -> Grid {
+    let mut grid = Grid::new_square(size);
+    for &(x, y) in live {
+        grid.set(x, y, true);
+    }
+    grid
+}
+
+fn live_cells(grid: &Grid) -> Vec<(usize, usize)> {
+    let mut live = Vec::new();
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if grid.get(x, y) {
+                live.push((x, y));
+            }
+        }
+    }
+    live
+}
+
+#[test]
+fn blinker_oscillates_with_period_two() {
+    let horizontal = [(1, 2), (2, 2), (3, 2)];
+    let vertical = [(2, 1), (2, 2), (2, 3)];
+    let mut grid = grid_with(5, &horizontal);
+
+    grid.step();
+    assert_eq!(live_cells(&grid), vertical);
+    grid.step();
+    assert_eq!(live_cells(&grid), horizontal);
+}
+
+#[test]
+fn block_is_a_still_life() {
+    let block = [(1, 1), (2, 1), (1, 2), (2, 2)];
+    let mut grid = grid_with(4, &block);
+
+    for _ in 0..3 {
+        grid.step();
+        assert_eq!(live_cells(&grid), block);
+    }
+}
+
+#[test]
+fn glider_translates_diagonally_every_four_generations() {
+    // Heading towards +x, -y (down and to the right on screen)
+    let glider = [(1, 1), (2, 1), (3, 1), (3, 2), (2, 3)];
+    let mut grid = grid_with(10, &glider);
+
+    for _ in 0..4 {
+        grid.step();
+    }
+    let expected: Vec<(usize, usize)> = {
+        let mut moved: Vec<_> = glider.iter().map(|&(x, y)| (x + 1, y - 1)).collect();
+        moved.sort_by_key(|&(x, y)| (y, x));
+        moved
+    };
+    assert_eq!(live_cells(&grid), expected);
+}
+
+#[test]
+fn lonely_cells_die_of_underpopulation() {
+    let mut grid = grid_with(5, &[(1, 1), (3, 3)]);
+    grid.step();
+    assert!(live_cells(&grid).is_empty());
+}
+
+#[test]
+fn crowded_cells_die_of_overpopulation() {
+    // The centre of a plus has four live neighbours
+    let plus = [(2, 1), (1, 2), (2, 2), (3, 2), (2, 3)];
+    let mut grid = grid_with(5, &plus);
+    grid.step();
+    assert!(!grid.get(2, 2));
+}
+
+#[test]
+fn dead_cell_with_three_neighbours_is_born() {
+    let mut grid = grid_with(5, &[(1, 1), (2, 1), (3, 1)]);
+    grid.step();
+    assert!(grid.get(2, 0));
+    assert!(grid.get(2, 2));
+}
+
+#[test]
+fn step_keeps_the_previous_generation() {
+    let mut grid = grid_with(5, &[(1, 2), (2, 2), (3, 2)]);
+    let before = grid.cells.clone();
+    grid.step();
+    assert_eq!(grid.prev_cells, before);
+    let middle = grid.cells.clone();
+    grid.step();
+    assert_eq!(grid.prev_cells, middle);
+    assert_eq!(grid.cells, before);
+}
+
+#[test]
+fn step_advances_the_generation() {
+    let mut grid = Grid::new_square(3);
+    grid.step();
+    grid.step();
+    assert_eq!(grid.generation, 2);
+}
+
+#[test]
+fn step_flags_a_board_that_stopped_changing() {
+    let mut block = grid_with(4, &[(1, 1), (2, 1), (1, 2), (2, 2)]);
+    block.step();
+    assert!(block.stable);
+    block.set(0, 0, true);
+    assert!(!block.stable, "edits clear the flag");
+
+    // Oscillators change every generation, and an emptied board is stable as well
+    let mut blinker = grid_with(5, &[(1, 2), (2, 2), (3, 2)]);
+    blinker.step();
+    assert!(!blinker.stable);
+    let mut lonely = grid_with(3, &[(1, 1)]);
+    lonely.step();
+    assert!(!lonely.stable);
+    lonely.step();
+    assert!(lonely.stable);
+    assert_eq!(lonely.count_live(), 0);
+}
+
+#[test]
+fn step_detects_a_period_two_cycle() {
+    let mut grid = grid_with(7, &[(1, 2), (2, 2), (3, 2)]);
+    grid.step();
+    assert!(!grid.oscillating, "needs two steps to compare against");
+    grid.step();
+    assert!(grid.oscillating);
+    assert!(!grid.stable);
+
+    grid.set(6, 6, true);
+    assert!(!grid.oscillating);
+    // The lone cell dies in the first step, but the board only matches two generations
+    // back once both are free of it
+    for _ in 0..2 {
+        grid.step();
+        assert!(!grid.oscillating);
+    }
+    grid.step();
+    assert!(grid.oscillating);
+
+    let mut block = grid_with(4, &[(1, 1), (2, 1), (1, 2), (2, 2)]);
+    block.step();
+    block.step();
+    assert!(block.stable && !block.oscillating);
+}
+
+#[test]
+fn step_finds_the_period_of_longer_cycles() {
+    let library = PatternLibrary::builtin();
+    let pulsar = library.get("pulsar").unwrap();
+    let mut grid = Grid::new_square(17);
+    grid.insert_pattern(2, 14, pulsar);
+    for _ in 0..2 {
+        grid.step();
+        assert_eq!(grid.period, None);
+    }
+    grid.step();
+    assert_eq!(grid.period, Some(3));
+
+    let mut block = grid_with(4, &[(1, 1), (2, 1), (1, 2), (2, 2)]);
+    block.step();
+    assert_eq!(block.period, Some(1));
+    block.set(0, 0, true);
+    assert_eq!(block.period, None, "edits start the search again");
+
+    // Cycles longer than the window go unnoticed
+    let mut short = Grid::new_square(17);
+    short.cycle_window = 2;
+    short.insert_pattern(2, 14, pulsar);
+    for _ in 0..9 {
+        short.step();
+        assert_eq!(short.period, None);
+    }
+}
+
+#[test]
+fn step_tracks_how_long_cells_have_lived() {
+    let mut grid = grid_with(5, &[(1, 2), (2, 2), (3, 2)]);
+    for _ in 0..3 {
+        grid.step();
+    }
+    // The blinker's centre never dies, while its arms are reborn every other generation
+    assert_eq!(grid.cell_age(2, 2), 3);
+    assert_eq!(grid.cell_age(2, 1), 1);
+    assert_eq!(grid.cell_age(1, 2), 0);
+
+    grid.set(2, 2, true);
+    assert_eq!(grid.cell_age(2, 2), 0, "edited cells start again");
+}
+
+#[test]
+fn blinker_wraps_across_the_short_edge_of_a_wide_grid() {
+    let mut grid = Grid::with_topology(8, 4, Topology::Toroidal);
+    for y in [3, 0, 1] {
+        grid.set(5, y, true);
+    }
+
+    grid.step();
+    assert_eq!(live_cells(&grid), [(4, 0), (5, 0), (6, 0)]);
+    grid.step();
+    assert_eq!(live_cells(&grid), [(5, 0), (5, 1), (5, 3)]);
+}
+
+#[test]
+fn glider_leaving_one_edge_reenters_on_the_opposite_one() {
+    let library = PatternLibrary::builtin();
+    let glider = library.get("glider").unwrap();
+    // Against the right edge, heading right and down, which is towards lower y
+    let mut grid = Grid::with_topology(10, 10, Topology::Toroidal);
+    grid.insert_pattern(7, 5, glider);
+    let start = live_cells(&grid);
+    assert!(start.iter().all(|&(x, _)| x >= 7));
+
+    // Each period moves it one cell right and one down, so its front column wraps to x = 0
+    for _ in 0..4 {
+        grid.step();
+    }
+    let mut expected = Grid::with_topology(10, 10, Topology::Toroidal);
+    expected.insert_pattern(7, 5, glider);
+    expected.shift_grid(1, -1);
+    assert_eq!(live_cells(&grid), live_cells(&expected));
+    assert!(live_cells(&grid).iter().any(|&(x, _)| x == 0));
+
+    // Ten periods take it all the way round to where it started
+    for _ in 0..36 {
+        grid.step();
+    }
+    assert_eq!(live_cells(&grid), start);
+}
+
+#[test]
+fn neighborhoods_count_different_cells() {
+    // A full 5x5 block: the centre sees 8 Moore neighbours, 4 von Neumann and 24 at range 2
+    let block: Vec<_> = (0..5).flat_map(|x| (0..5).map(move |y| (x, y))).collect();
+    let mut grid = grid_with(5, &block);
+    assert_eq!(grid.neighbors(2, 2), 8);
+    grid.neighborhood = Neighborhood::VonNeumann;
+    assert_eq!(grid.neighbors(2, 2), 4);
+    assert_eq!(grid.neighbors(0, 0), 2);
+    grid.neighborhood = Neighborhood::MooreRange(2);
+    assert_eq!(grid.neighbors(2, 2), 24);
+    assert_eq!(grid.neighbors(0, 0), 8);
+}
+
+#[test]
+fn neighborhood_names_parse() {
+    assert_eq!(Neighborhood::parse("Moore"), Some(Neighborhood::Moore));
+    assert_eq!(
+        Neighborhood::parse("von-neumann"),
+        Some(Neighborhood::VonNeumann)
+    );
+    assert_eq!(
+        Neighborhood::parse("moore:3"),
+        Some(Neighborhood::MooreRange(3))
+    );
+    assert_eq!(Neighborhood::parse("moore:0"), None);
+    assert_eq!(Neighborhood::parse("hex"), None);
+}
+
+#[test]
+fn neighborhoods_display_as_names_that_parse_back() {
+    for neighborhood in [
+        Neighborhood::Moore,
+        Neighborhood::VonNeumann,
+        Neighborhood::MooreRange(2),
+    ] {
+        let name = neighborhood.to_string();
+        assert_eq!(Neighborhood::parse(&name), Some(neighborhood), "{}", name);
+    }
+}
+
+#[test]
+fn von_neumann_ignores_diagonal_neighbours() {
+    // An X: the centre has four diagonal neighbours and no orthogonal ones
+    let mut grid = grid_with(3, &[(0, 0), (2, 0), (1, 1), (0, 2), (2, 2)]);
+    assert_eq!(grid.neighbors(1, 1), 4);
+    assert_eq!(grid.neighbors(1, 0), 3);
+    grid.neighborhood = Neighborhood::VonNeumann;
+    assert_eq!(grid.neighbors(1, 1), 0);
+    assert_eq!(grid.neighbors(1, 0), 3);
+}
+
+#[test]
+fn von_neumann_births_skip_the_diagonals() {
+    // Under B1/S1 the lone cell's orthogonal neighbours are born and it dies itself, while
+    // the diagonal cells never see it
+    let mut grid = grid_with(5, &[(2, 2)]);
+    grid.neighborhood = Neighborhood::VonNeumann;
+    grid.ruleset = Ruleset::parse("B1/S1").unwrap();
+    grid.step();
+    assert_eq!(live_cells(&grid), [(2, 1), (1, 2), (3, 2), (2, 3)]);
+}
+
+// Flips every cell whatever its neighbours
+struct Invert;
+
+impl CellularAutomaton for Invert {
+    type Cell = bool;
+
+    fn next_state(&self, alive: bool, _neighbors: usize) -> bool {
+        !alive
+    }
+}
+
+#[test]
+fn step_with_uses_the_given_automaton() {
+    let mut grid = grid_with(3, &[(1, 1)]);
+    grid.step_with(&Invert);
+    assert_eq!(grid.count_live(), 8);
+    assert!(!grid.get(1, 1));
+    assert_eq!(grid.generation, 1);
+
+    // The grid's own ruleset is just another automaton
+    let blinker = [(1, 2), (2, 2), (3, 2)];
+    let (mut stepped, mut stepped_with) = (grid_with(5, &blinker), grid_with(5, &blinker));
+    stepped.step();
+    stepped_with.step_with(&Ruleset::default());
+    assert_eq!(stepped.cells, stepped_with.cells);
+}
+
+#[test]
+fn generations_notations_agree() {
+    let brain = Ruleset::brians_brain();
+    assert_eq!(Ruleset::parse("B2/S/C3"), Ok(brain));
+    assert_eq!(Ruleset::parse("/2/3"), Ok(brain));
+    assert_eq!(brain.states, 3);
+    assert_eq!(brain.to_string(), "B2/S/C3");
+    assert!(Ruleset::parse("B3/S23/C1").is_err());
+}
+
+#[test]
+fn dying_cells_decay_and_block_births() {
+    let row = [(1, 2), (2, 2), (3, 2)];
+    let mut grid = grid_with(5, &row);
+    grid.ruleset = Ruleset::brians_brain();
+    grid.step();
+    for &(x, y) in &row {
+        assert!(!grid.get(x, y));
+        assert_eq!(grid.state(x, y), 2);
+    }
+    assert_eq!(live_cells(&grid), [(1, 1), (3, 1), (1, 3), (3, 3)]);
+
+    // (1, 2) now has two live neighbours, but is still dying so can't be born
+    grid.step();
+    assert!(!grid.get(1, 2));
+    assert_eq!(grid.state(1, 2), 0);
+    assert_eq!(grid.state(1, 1), 2);
+
+    let mut two_state = grid_with(5, &row);
+    two_state.ruleset = Ruleset::parse("B2/S").unwrap();
+    two_state.step();
+    two_state.step();
+    assert!(two_state.get(1, 2));
+}
+
+// Run with `cargo test --release -- --ignored --nocapture` to time the stepping hot path
+#[test]
+#[ignore]
+fn time_one_thousand_steps() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut grid = Grid::new_square(200);
+    grid.randomize(0.2, &mut StdRng::seed_from_u64(0));
+    let start = std::time::Instant::now();
+    for _ in 0..1000 {
+        grid.step();
+    }
+    println!("1000 steps of a 200x200 grid took {:?}", start.elapsed());
+}
+
+#[test]
+fn highlife_notation_parses() {
+    let highlife = Ruleset::parse("B36/S23").unwrap();
+    let counts = |flags: [bool; 9]| (0..9).filter(|&n| flags[n]).collect::<Vec<_>>();
+    assert_eq!(counts(highlife.birth), [3, 6]);
+    assert_eq!(counts(highlife.survive), [2, 3]);
+    assert_eq!(highlife.states, 2);
+    assert_eq!(highlife.to_string(), "B36/S23");
+}
+
+#[test]
+fn highlife_replicator_copies_itself() {
+    let replicator = parse_rle("x = 5, y = 5, rule = B36/S23\n2b3o$bo2bo$o3bo$o2bo$3o!").unwrap();
+    let mut grid = Grid::new_square(24);
+    grid.ruleset = Ruleset::parse("B36/S23").unwrap();
+    grid.insert_pattern(10, 13, &replicator);
+
+    // Twelve generations on there are exactly two copies, two cells out along the diagonal
+    // either side of the original
+    for _ in 0..12 {
+        grid.step();
+    }
+    let mut expected = Grid::new_square(24);
+    expected.insert_pattern(8, 15, &replicator);
+    expected.insert_pattern(12, 11, &replicator);
+    assert_eq!(live_cells(&grid), live_cells(&expected));
+
+    // Under Conway's rule the same cells don't replicate
+    let mut conway = Grid::new_square(24);
+    conway.insert_pattern(10, 13, &replicator);
+    for _ in 0..12 {
+        conway.step();
+    }
+    assert_ne!(live_cells(&conway), live_cells(&expected));
+}